@@ -0,0 +1,18 @@
+CREATE TYPE user_token_purpose_enum AS ENUM ('PASSWORD_RESET');
+
+-- Single-use tokens sent to the user by mail.
+CREATE TABLE IF NOT EXISTS user_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+
+    purpose user_token_purpose_enum NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+
+    used_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_tokens_user_id ON user_tokens(user_id, purpose);
@@ -0,0 +1,7 @@
+ALTER TYPE user_token_purpose_enum ADD VALUE IF NOT EXISTS 'EMAIL_VERIFICATION';
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMPTZ;
+
+-- Accounts created before email verification existed are treated as verified.
+UPDATE users SET is_email_verified = TRUE, email_verified_at = created_at;
@@ -0,0 +1,28 @@
+CREATE TYPE login_failure_reason_enum AS ENUM ('UNKNOWN_EMAIL', 'INVALID_PASSWORD', 'INVALID_TWO_FACTOR', 'LOCKED_OUT');
+
+-- Audit trail of failed signin attempts.
+CREATE TABLE IF NOT EXISTS login_attempts (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT,
+    email TEXT NOT NULL,
+
+    reason login_failure_reason_enum NOT NULL,
+    user_agent TEXT,
+    ip_address TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_attempts_user_id ON login_attempts(user_id);
+CREATE INDEX IF NOT EXISTS idx_login_attempts_ip_address ON login_attempts(ip_address);
+
+-- Failure counters keyed by account (`account:<email>`) or client (`ip:<address>`).
+CREATE TABLE IF NOT EXISTS login_throttles (
+    throttle_key TEXT PRIMARY KEY,
+    failure_count INTEGER NOT NULL DEFAULT 0,
+
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_until TIMESTAMPTZ
+);
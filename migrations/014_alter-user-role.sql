@@ -0,0 +1,9 @@
+-- The first admin has to be promoted by hand:
+-- UPDATE users SET role = 'ADMIN' WHERE email = '...';
+CREATE TYPE user_role_enum AS ENUM ('USER', 'MODERATOR', 'ADMIN');
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role user_role_enum NOT NULL DEFAULT 'USER';
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_suspended BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_users_role ON users(role);
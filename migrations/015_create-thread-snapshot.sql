@@ -0,0 +1,36 @@
+-- Frozen order of a score ordered list (popularity, subthreads by votes). Scores
+-- change between requests, so every page after the first is read from the order
+-- captured when the first page was requested.
+-- Every request of the same time bucket shares one snapshot instead of ranking the
+-- list again, and a snapshot is ranked a chunk at a time, the next chunk once paging
+-- reaches the end of the previous one.
+CREATE TABLE IF NOT EXISTS thread_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    -- NULL for the popularity ranking
+    parent_thread BIGINT,
+    bucket_at TIMESTAMPTZ NOT NULL,
+
+    entry_count BIGINT NOT NULL DEFAULT 0,
+    -- set once a chunk comes back short, every thread of the list is then ranked
+    is_complete BOOLEAN NOT NULL DEFAULT FALSE,
+
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(parent_thread) REFERENCES thread(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS thread_snapshot_entries (
+    snapshot_id BIGINT NOT NULL,
+    position BIGINT NOT NULL,
+    thread_id BIGINT NOT NULL,
+
+    PRIMARY KEY(snapshot_id, position),
+    FOREIGN KEY(snapshot_id) REFERENCES thread_snapshots(id) ON DELETE CASCADE,
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_thread_snapshots_expires_at ON thread_snapshots(expires_at);
+-- thread ids start at 1, so 0 stands for the popularity ranking
+CREATE UNIQUE INDEX IF NOT EXISTS idx_thread_snapshots_bucket
+    ON thread_snapshots(COALESCE(parent_thread, 0), bucket_at);
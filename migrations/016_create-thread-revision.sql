@@ -0,0 +1,23 @@
+-- Every version of a thread, the first one is the content it was posted with.
+CREATE TABLE IF NOT EXISTS thread_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    thread_id BIGINT NOT NULL,
+    revision INTEGER NOT NULL,
+    -- NULL once the editor's account is removed
+    editor_id BIGINT,
+
+    title TEXT,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE(thread_id, revision),
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE,
+    FOREIGN KEY(editor_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+ALTER TABLE thread ADD COLUMN IF NOT EXISTS edit_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE thread ADD COLUMN IF NOT EXISTS edited BOOLEAN GENERATED ALWAYS AS (edit_count > 0) STORED;
+
+INSERT INTO thread_revisions (thread_id, revision, editor_id, title, content, created_at)
+SELECT id, 1, user_id, title, content, created_at FROM thread
+ON CONFLICT (thread_id, revision) DO NOTHING;
@@ -0,0 +1,3 @@
+-- Version of the current content, i.e. its revision number. Sent as the ETag of a
+-- thread and checked against `If-Match` when it is edited.
+ALTER TABLE thread ADD COLUMN IF NOT EXISTS version INTEGER GENERATED ALWAYS AS (edit_count + 1) STORED;
@@ -0,0 +1,11 @@
+-- Who deleted a thread, an author cannot restore a thread removed by a moderator.
+ALTER TABLE thread ADD COLUMN IF NOT EXISTS deleted_by BIGINT;
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_thread_deleted_by') THEN
+        ALTER TABLE thread ADD CONSTRAINT fk_thread_deleted_by
+            FOREIGN KEY(deleted_by) REFERENCES users(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_thread_deleted_at ON thread(deleted_at) WHERE is_deleted = TRUE;
@@ -0,0 +1,16 @@
+-- Full-text search over threads, matches in the title rank above matches in the content.
+ALTER TABLE thread ADD COLUMN IF NOT EXISTS search_vector TSVECTOR
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', COALESCE(title, '')), 'A') ||
+        setweight(to_tsvector('english', content), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_thread_search_vector ON thread USING GIN(search_vector);
+
+-- Search highlights are HTML, the text around the `<mark>` tags is escaped with this
+-- before `ts_headline` adds them.
+CREATE OR REPLACE FUNCTION html_escape(value TEXT) RETURNS TEXT
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT replace(replace(replace(replace(replace(value,
+        '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+$$;
@@ -0,0 +1,6 @@
+-- Handle and name autocomplete, prefix and fuzzy matching.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_users_handle_trgm ON users USING GIN(LOWER(handle) gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_users_name_trgm ON users USING GIN(LOWER(name) gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS idx_follow_follower_id ON follow(follower_id);
@@ -0,0 +1,35 @@
+-- `#tags` parsed out of the thread content, stored lowercase without the `#`.
+CREATE TABLE IF NOT EXISTS tags (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS thread_tags (
+    thread_id BIGINT NOT NULL,
+    tag_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY(thread_id, tag_id),
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE,
+    FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_thread_tags_tag_id ON thread_tags(tag_id);
+
+-- Tags of the existing threads, same rules as `utils::text::parse_tags`
+-- without its per-thread limit.
+INSERT INTO tags (name)
+SELECT DISTINCT LOWER(m[1])
+FROM thread t,
+regexp_matches(t.content, '(?:^|[^[:alnum:]_])#([[:alnum:]_]+)', 'g') AS m
+WHERE m[1] ~ '[[:alpha:]]'
+AND char_length(m[1]) <= 50
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO thread_tags (thread_id, tag_id, created_at)
+SELECT DISTINCT t.id, tg.id, t.created_at
+FROM thread t,
+regexp_matches(t.content, '(?:^|[^[:alnum:]_])#([[:alnum:]_]+)', 'g') AS m
+JOIN tags tg ON tg.name = LOWER(m[1])
+ON CONFLICT (thread_id, tag_id) DO NOTHING;
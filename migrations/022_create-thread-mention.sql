@@ -0,0 +1,14 @@
+-- `@handle` mentions resolved to a user, offsets are in characters of the content.
+CREATE TABLE IF NOT EXISTS thread_mentions (
+    thread_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    start_offset INTEGER NOT NULL,
+    end_offset INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY(thread_id, start_offset),
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_thread_mentions_user_id ON thread_mentions(user_id);
@@ -0,0 +1,49 @@
+CREATE TYPE notification_type_enum AS ENUM ('FOLLOW', 'REPLY', 'UPVOTE', 'MENTION');
+
+-- Events of the same type on the same thread are grouped while unread, e.g.
+-- "12 people upvoted your thread". Follows are grouped with a NULL thread_id.
+CREATE TABLE IF NOT EXISTS notifications (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    type notification_type_enum NOT NULL,
+    thread_id BIGINT,
+    actor_count INTEGER NOT NULL DEFAULT 0,
+    -- NULL once the last actor's account is removed
+    last_actor_id BIGINT,
+
+    is_read BOOLEAN NOT NULL DEFAULT FALSE,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE,
+    FOREIGN KEY(last_actor_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- At most one unread group per recipient, type and thread
+CREATE UNIQUE INDEX IF NOT EXISTS idx_notifications_unread_group
+    ON notifications(user_id, type, COALESCE(thread_id, 0)) WHERE is_read = FALSE;
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id ON notifications(user_id, updated_at DESC, id DESC);
+
+-- Who is behind a group, each actor counts once.
+CREATE TABLE IF NOT EXISTS notification_actors (
+    notification_id BIGINT NOT NULL,
+    actor_id BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY(notification_id, actor_id),
+    FOREIGN KEY(notification_id) REFERENCES notifications(id) ON DELETE CASCADE,
+    FOREIGN KEY(actor_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Every type is enabled unless a row turns it off.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id BIGINT NOT NULL,
+    type notification_type_enum NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY(user_id, type),
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+-- Named folders to sort bookmarks into, names are unique per user.
+CREATE TABLE IF NOT EXISTS bookmark_folders (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bookmark_folders_user_name
+    ON bookmark_folders(user_id, LOWER(name));
+
+-- Saved threads, `folder_id` is NULL for the ones not in a folder. Deleting a
+-- folder keeps its bookmarks.
+CREATE TABLE IF NOT EXISTS bookmarks (
+    user_id BIGINT NOT NULL,
+    thread_id BIGINT NOT NULL,
+    folder_id BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY(user_id, thread_id),
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(thread_id) REFERENCES thread(id) ON DELETE CASCADE,
+    FOREIGN KEY(folder_id) REFERENCES bookmark_folders(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bookmarks_user_id ON bookmarks(user_id, created_at DESC, thread_id DESC);
+CREATE INDEX IF NOT EXISTS idx_bookmarks_folder_id ON bookmarks(folder_id);
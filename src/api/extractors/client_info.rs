@@ -0,0 +1,48 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts},
+};
+
+use crate::{config, domain::model::session::ClientInfo, error::CustomError};
+
+// Extracts the user agent and the client IP of a request.
+// `X-Forwarded-For` is only honored when `TRUST_PROXY_HEADERS` is enabled,
+// otherwise anyone could spoof their address.
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = CustomError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let forwarded_ip = if config::env::envs().trust_proxy_headers {
+            parts
+                .headers
+                .get("X-Forwarded-For")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.split(',').next())
+                .map(|value| value.trim().to_string())
+        } else {
+            None
+        };
+        let ip_address = forwarded_ip.or_else(|| {
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip().to_string())
+        });
+
+        Ok(ClientInfo { user_agent, ip_address })
+    }
+}
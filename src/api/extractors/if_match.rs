@@ -0,0 +1,41 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+
+use crate::error::CustomError;
+
+// The version required by an `If-Match` header, e.g. `If-Match: "3"`.
+// `None` when the header is absent or `*`.
+pub struct IfMatch(pub Option<i32>);
+
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = CustomError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+        let value = value.to_str().map_err(|_| CustomError::PreconditionFailed)?.trim();
+        if value == "*" {
+            return Ok(IfMatch(None));
+        }
+        // weak validators are compared like strong ones, a thread has a single representation
+        let version = value
+            .trim_start_matches("W/")
+            .trim_matches('"')
+            .parse::<i32>()
+            .map_err(|_| CustomError::PreconditionFailed)?;
+        Ok(IfMatch(Some(version)))
+    }
+}
+
+pub fn etag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
@@ -0,0 +1,2 @@
+pub mod client_info;
+pub mod if_match;
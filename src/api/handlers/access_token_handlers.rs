@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{user::RequestCreateAccessToken, SuccessResponse},
+        model::jwt_claims::JwtClaims,
+    },
+    error::CustomError,
+};
+
+// POST api/user/me/tokens
+pub async fn create_access_token(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(token_dto): Json<RequestCreateAccessToken>,
+) -> Result<impl IntoResponse, CustomError> {
+    let access_token = state
+        .access_token_service
+        .create_access_token(token_context.id, token_dto)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to create access token. Copy it now, it will not be shown again.",
+        Some(access_token),
+    )))
+}
+
+// GET api/user/me/tokens
+pub async fn list_access_token(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let access_token_list =
+        state.access_token_service.list_access_token(token_context.id).await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch access token list",
+        Some(access_token_list),
+    )))
+}
+
+// DELETE api/user/me/tokens/{id}
+pub async fn revoke_access_token(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.access_token_service.revoke_access_token(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to revoke access token", None)))
+}
@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{user::RequestUpdateUserRole, RequestCursorParmas, SuccessResponse},
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// GET api/admin/users
+pub async fn list_user(
+    State(state): State<AppState>,
+    Query(params): Query<RequestCursorParmas>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::AdminUser,
+    )?;
+    let user_list = state.admin_service.list_user(cursor, limit).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch user list", Some(user_list))))
+}
+
+// PUT api/admin/users/{id}/role
+pub async fn update_user_role(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+    Json(role_dto): Json<RequestUpdateUserRole>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.admin_service.update_user_role(token_context.id, id, role_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to update user role", None)))
+}
+
+// POST api/admin/users/{id}/suspend
+pub async fn suspend_user(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.admin_service.suspend_user(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to suspend user", None)))
+}
+
+// DELETE api/admin/users/{id}/suspend
+pub async fn unsuspend_user(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.admin_service.unsuspend_user(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to unsuspend user", None)))
+}
@@ -1,10 +1,17 @@
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{extract::State, response::IntoResponse, Extension, Json};
 
 use crate::{
     api::state::AppState,
-    domain::dto::{
-        user::{RequestSignin, RequestSignup},
-        SuccessResponse,
+    domain::{
+        dto::{
+            user::{
+                RequestDisableTwoFactor, RequestForgotPassword, RequestRefreshToken,
+                RequestResetPassword, RequestSignin, RequestSignup, RequestTwoFactorCode,
+                RequestTwoFactorSignin, RequestVerifyEmail, ResponseSigninResult,
+            },
+            SuccessResponse,
+        },
+        model::{jwt_claims::JwtClaims, session::ClientInfo},
     },
     error::CustomError,
 };
@@ -23,10 +30,122 @@ pub async fn signup(
 // POST api/user/signin
 pub async fn signin(
     State(state): State<AppState>,
+    client_info: ClientInfo,
     Json(signin_dto): Json<RequestSignin>,
 ) -> Result<impl IntoResponse, CustomError> {
-    match state.user_service.signin(signin_dto).await {
-        Ok(token) => Ok(Json(SuccessResponse::new("Success to login", Some(token)))),
+    match state.user_service.signin(signin_dto, client_info).await {
+        Ok(result @ ResponseSigninResult::Authenticated(_)) => {
+            Ok(Json(SuccessResponse::new("Success to login", Some(result))))
+        }
+        Ok(result @ ResponseSigninResult::TwoFactorRequired(_)) => Ok(Json(
+            SuccessResponse::new("Two-factor authentication required", Some(result)),
+        )),
         Err(err) => Err(err),
     }
 }
+
+// POST api/auth/refresh
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(refresh_dto): Json<RequestRefreshToken>,
+) -> Result<impl IntoResponse, CustomError> {
+    let token = state.user_service.refresh(refresh_dto).await?;
+    Ok(Json(SuccessResponse::new("Success to refresh token", Some(token))))
+}
+
+// POST api/auth/signout
+pub async fn signout(
+    State(state): State<AppState>,
+    Json(refresh_dto): Json<RequestRefreshToken>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.signout(refresh_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to sign out", None)))
+}
+
+// POST api/auth/password/forgot
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(forgot_dto): Json<RequestForgotPassword>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.forgot_password(forgot_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new(
+        "If the email is registered, a password reset link has been sent",
+        None,
+    )))
+}
+
+// POST api/auth/password/reset
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(reset_dto): Json<RequestResetPassword>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.reset_password(reset_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to reset password", None)))
+}
+
+// POST api/auth/verify-email
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(verify_dto): Json<RequestVerifyEmail>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.verify_email(verify_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to verify email", None)))
+}
+
+// POST api/auth/verify-email/resend
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.resend_verification_email(token_context.id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Verification mail has been sent", None)))
+}
+
+// POST api/auth/signin/2fa
+pub async fn signin_two_factor(
+    State(state): State<AppState>,
+    client_info: ClientInfo,
+    Json(signin_dto): Json<RequestTwoFactorSignin>,
+) -> Result<impl IntoResponse, CustomError> {
+    let token = state.user_service.signin_two_factor(signin_dto, client_info).await?;
+    Ok(Json(SuccessResponse::new("Success to login", Some(token))))
+}
+
+// POST api/auth/2fa/enroll
+pub async fn enroll_two_factor(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let enrollment = state.user_service.enroll_two_factor(token_context.id).await?;
+    Ok(Json(SuccessResponse::new(
+        "Scan the provisioning URI and confirm with a code",
+        Some(enrollment),
+    )))
+}
+
+// POST api/auth/2fa/confirm
+pub async fn confirm_two_factor(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(code_dto): Json<RequestTwoFactorCode>,
+) -> Result<impl IntoResponse, CustomError> {
+    let recovery_codes =
+        state.user_service.confirm_two_factor(token_context.id, code_dto).await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to enable two-factor authentication",
+        Some(recovery_codes),
+    )))
+}
+
+// POST api/auth/2fa/disable
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(disable_dto): Json<RequestDisableTwoFactor>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.disable_two_factor(token_context.id, disable_dto).await?;
+    Ok(Json(SuccessResponse::<String>::new(
+        "Success to disable two-factor authentication",
+        None,
+    )))
+}
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{
+            bookmark::{
+                RequestBookmarkFolder, RequestBookmarkListParams,
+                RequestBookmarkThreadParams, RequestMoveBookmark,
+            },
+            SuccessResponse,
+        },
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// POST api/thread/{id}/bookmark?folder_id=
+pub async fn bookmark_thread(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+    Query(params): Query<RequestBookmarkThreadParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    state
+        .bookmark_service
+        .bookmark_thread(token_context.id, id, params.folder_id)
+        .await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to bookmark thread", None)))
+}
+
+// DELETE api/thread/{id}/bookmark
+pub async fn unbookmark_thread(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.bookmark_service.unbookmark_thread(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to remove bookmark", None)))
+}
+
+// GET api/user/me/bookmarks?folder_id=
+pub async fn list_bookmarked_thread(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Query(params): Query<RequestBookmarkListParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Bookmark,
+    )?;
+    state.bookmark_service.check_folder(token_context.id, params.folder_id).await?;
+    let thread_list = state
+        .thread_service
+        .list_bookmarked_thread(token_context.id, params.folder_id, cursor, limit)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch bookmarked threads",
+        Some(thread_list),
+    )))
+}
+
+// PUT api/user/me/bookmarks/{thread_id}
+pub async fn move_bookmark(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(thread_id): Path<i64>,
+    Json(move_dto): Json<RequestMoveBookmark>,
+) -> Result<impl IntoResponse, CustomError> {
+    state
+        .bookmark_service
+        .move_bookmark(token_context.id, thread_id, move_dto.folder_id)
+        .await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to move bookmark", None)))
+}
+
+// GET api/user/me/bookmarks/folders
+pub async fn list_bookmark_folder(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let folders = state.bookmark_service.list_folder(token_context.id).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch bookmark folders", Some(folders))))
+}
+
+// POST api/user/me/bookmarks/folders
+pub async fn create_bookmark_folder(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(folder_dto): Json<RequestBookmarkFolder>,
+) -> Result<impl IntoResponse, CustomError> {
+    let folder =
+        state.bookmark_service.create_folder(token_context.id, &folder_dto.name).await?;
+    Ok(Json(SuccessResponse::new("Success to create bookmark folder", Some(folder))))
+}
+
+// PUT api/user/me/bookmarks/folders/{id}
+pub async fn rename_bookmark_folder(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+    Json(folder_dto): Json<RequestBookmarkFolder>,
+) -> Result<impl IntoResponse, CustomError> {
+    let folder = state
+        .bookmark_service
+        .rename_folder(token_context.id, id, &folder_dto.name)
+        .await?;
+    Ok(Json(SuccessResponse::new("Success to rename bookmark folder", Some(folder))))
+}
+
+// DELETE api/user/me/bookmarks/folders/{id}
+pub async fn delete_bookmark_folder(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.bookmark_service.delete_folder(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to delete bookmark folder", None)))
+}
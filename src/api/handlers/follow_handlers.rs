@@ -8,7 +8,7 @@ use crate::{
     api::state::AppState,
     domain::{
         dto::{RequestCursorParmas, SuccessResponse},
-        model::jwt_claims::JwtClaims,
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
     },
     error::CustomError,
     utils,
@@ -24,7 +24,7 @@ pub async fn follow(
         Ok(success) => {
             if success {
                 return Ok(Json(SuccessResponse::<String>::new(
-                    &"Success to follow user",
+                    "Success to follow user",
                     None,
                 )));
             }
@@ -44,7 +44,7 @@ pub async fn unfollow(
         Ok(success) => {
             if success {
                 return Ok(Json(SuccessResponse::<String>::new(
-                    &"Success to unfollow user",
+                    "Success to unfollow user",
                     None,
                 )));
             }
@@ -60,8 +60,11 @@ pub async fn list_user_follower(
     Path(handle): Path<String>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Follower,
+    )?;
     match state.follow_service.list_user_follower(&handle, cursor, limit).await {
         Ok(thread_list) => Ok(Json(SuccessResponse::new(
             "Success to fetch follower list",
@@ -77,8 +80,11 @@ pub async fn list_user_following(
     Path(handle): Path<String>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Following,
+    )?;
     match state.follow_service.list_user_following(&handle, cursor, limit).await {
         Ok(following_list) => Ok(Json(SuccessResponse::new(
             "Success to fetch following list",
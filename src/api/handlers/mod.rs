@@ -1,5 +1,13 @@
+pub mod access_token_handlers;
+pub mod admin_handlers;
 pub mod auth_handlers;
+pub mod bookmark_handlers;
 pub mod follow_handlers;
+pub mod notification_handlers;
+pub mod search_handlers;
+pub mod stream_handlers;
+pub mod tag_handlers;
 pub mod thread_handlers;
 pub mod user_handlers;
 pub mod votes_handlers;
+pub mod webhook_handlers;
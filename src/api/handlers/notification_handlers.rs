@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{
+            notification::{NotificationPreferences, RequestNotificationParams},
+            SuccessResponse,
+        },
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// GET api/user/me/notifications
+pub async fn list_notification(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Query(params): Query<RequestNotificationParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Notification,
+    )?;
+    let notifications = state
+        .notification_service
+        .list_notification(token_context.id, params, cursor, limit)
+        .await?;
+    Ok(Json(SuccessResponse::new("Success to fetch notifications", Some(notifications))))
+}
+
+// GET api/user/me/notifications/unread-count
+pub async fn count_unread_notification(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let count =
+        state.notification_service.count_unread_notification(token_context.id).await?;
+    Ok(Json(SuccessResponse::new("Success to count unread notifications", Some(count))))
+}
+
+// POST api/user/me/notifications/{id}/read
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.notification_service.mark_read(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to mark notification as read", None)))
+}
+
+// POST api/user/me/notifications/read
+pub async fn mark_all_notification_read(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let _count = state.notification_service.mark_all_read(token_context.id).await?;
+    Ok(Json(SuccessResponse::<String>::new(
+        "Success to mark all notifications as read",
+        None,
+    )))
+}
+
+// GET api/user/me/notifications/preferences
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let preferences =
+        state.notification_service.get_preferences(token_context.id).await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch notification preferences",
+        Some(preferences),
+    )))
+}
+
+// PUT api/user/me/notifications/preferences
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(preferences): Json<NotificationPreferences>,
+) -> Result<impl IntoResponse, CustomError> {
+    let preferences = state
+        .notification_service
+        .update_preferences(token_context.id, preferences)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to update notification preferences",
+        Some(preferences),
+    )))
+}
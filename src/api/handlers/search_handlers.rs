@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{
+            search::{RequestSearchThreadParams, RequestSearchUserParams},
+            SuccessResponse,
+        },
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// GET api/search/threads
+pub async fn search_thread(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Query(params): Query<RequestSearchThreadParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Search,
+    )?;
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+    let result =
+        state.thread_service.search_thread(params, cursor, limit, viewer_id).await?;
+    Ok(Json(SuccessResponse::new("Success to search threads", Some(result))))
+}
+
+// GET api/search/users
+pub async fn search_user(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Query(params): Query<RequestSearchUserParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let searcher_id = token_context.map(|Extension(claims)| claims.id);
+    let user_list = state.user_service.search_user(searcher_id, params).await?;
+    Ok(Json(SuccessResponse::new("Success to search users", Some(user_list))))
+}
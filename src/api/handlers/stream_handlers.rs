@@ -0,0 +1,119 @@
+use std::{collections::HashSet, convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        IntoResponse,
+    },
+    Extension,
+};
+use chrono::Utc;
+use tokio::time::{interval_at, timeout_at, Instant};
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{dto::RequestStreamParams, model::jwt_claims::JwtClaims},
+    error::CustomError,
+};
+
+const MAX_STREAM_THREADS: usize = 50;
+// How long a revoked session or personal access token can keep its stream open.
+const STREAM_AUTH_CHECK_INTERVAL_IN_SECONDS: u64 = 30;
+
+// GET api/stream?threads=1,2,3
+// Server-Sent Events: the user's notifications, and the replies and vote counts of
+// the threads in `threads`. A `lagged` event means some were missed, the client
+// should refetch what it shows. The stream ends when the access token expires or
+// is revoked, the client reconnects with a valid one.
+pub async fn stream(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    headers: HeaderMap,
+    Query(params): Query<RequestStreamParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let threads = parse_threads(params.threads.as_deref())?;
+    let user_id = token_context.id;
+    // personal access tokens are verified again from the token itself
+    let access_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|auth_header| auth_header.to_str().ok())
+        .and_then(|auth_str| auth_str.strip_prefix("Bearer "))
+        .filter(|_| token_context.is_access_token())
+        .map(str::to_string);
+    let receiver = state.event_bus.subscribe();
+    let signed_out =
+        tokio_stream::once(wait_until_signed_out(state, token_context, access_token))
+            .then(|signed_out| signed_out)
+            .map(|_| None);
+
+    let events = BroadcastStream::new(receiver).filter_map(move |event| {
+        let sse_event = match event {
+            Ok(event) if event.is_visible_to(user_id, &threads) => SseEvent::default()
+                .event(event.name())
+                .json_data(&event)
+                .unwrap_or_else(|_| SseEvent::default().event("lagged")),
+            Ok(_) => return None,
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                SseEvent::default().event("lagged").data(missed.to_string())
+            }
+        };
+        Some(Some(Ok::<_, Infallible>(sse_event)))
+    });
+    // `signed_out` yields a single `None`, which ends the stream
+    let events = events.merge(signed_out).map_while(|event| event);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+// Resolves once the token the stream was opened with expires, or its session or
+// personal access token no longer verifies.
+async fn wait_until_signed_out(
+    state: AppState,
+    token_context: JwtClaims,
+    access_token: Option<String>,
+) {
+    let period = Duration::from_secs(STREAM_AUTH_CHECK_INTERVAL_IN_SECONDS);
+    let revoked = async {
+        let mut interval = interval_at(Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            let verified = match &access_token {
+                Some(token) => state
+                    .access_token_service
+                    .verify_access_token(token)
+                    .await
+                    .map(|_| ()),
+                None => state.user_service.verify_session(&token_context).await,
+            };
+            if verified.is_err() {
+                return;
+            }
+        }
+    };
+    match token_context.expires_at() {
+        Some(expires_at) => {
+            let left = (expires_at - Utc::now()).to_std().unwrap_or_default();
+            let _ = timeout_at(Instant::now() + left, revoked).await;
+        }
+        None => revoked.await,
+    }
+}
+
+fn parse_threads(threads: Option<&str>) -> Result<HashSet<i64>, CustomError> {
+    let threads = threads
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<i64>().map_err(|_| CustomError::InvalidQuery))
+        .collect::<Result<HashSet<i64>, CustomError>>()?;
+    if threads.len() > MAX_STREAM_THREADS {
+        return Err(CustomError::InvalidQuery);
+    }
+    Ok(threads)
+}
@@ -0,0 +1,42 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{thread::RequestTrendingTagParams, RequestCursorParmas, SuccessResponse},
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// GET api/tag/{tag}/threads
+pub async fn list_thread_by_tag(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Path(tag): Path<String>,
+    Query(params): Query<RequestCursorParmas>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Tag,
+    )?;
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+    let thread_list =
+        state.thread_service.list_thread_by_tag(&tag, cursor, limit, viewer_id).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch tagged threads", Some(thread_list))))
+}
+
+// GET api/tag/trending
+pub async fn list_trending_tag(
+    State(state): State<AppState>,
+    Query(params): Query<RequestTrendingTagParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let tag_list = state.thread_service.list_trending_tag(params.limit).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch trending tags", Some(tag_list))))
+}
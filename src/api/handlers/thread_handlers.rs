@@ -1,17 +1,21 @@
 use axum::{
     extract::{Path, Query, State},
+    http::header,
     response::IntoResponse,
     Extension, Json,
 };
 
 use crate::{
-    api::state::AppState,
+    api::{
+        extractors::if_match::{etag, IfMatch},
+        state::AppState,
+    },
     domain::{
         dto::{
-            thread::{RequestCreateThread, RequestUpdateThread},
+            thread::{RequestCreateThread, RequestThreadTreeParams, RequestUpdateThread},
             RequestCursorParmas, SuccessResponse,
         },
-        model::jwt_claims::JwtClaims,
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
     },
     error::CustomError,
     utils,
@@ -31,51 +35,147 @@ pub async fn create_thread(
 // GET api/thread/{id}
 pub async fn get_thread_by_id(
     State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let thread = state.thread_service.get_thread_by_id(id).await?;
-    Ok(Json(SuccessResponse::new("Success to fetch thread", Some(thread))))
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+    let thread = state.thread_service.get_thread_by_id(id, viewer_id).await?;
+    Ok((
+        [(header::ETAG, etag(thread.version))],
+        Json(SuccessResponse::new("Success to fetch thread", Some(thread))),
+    ))
 }
 
 // GET api/thread/{id}/subthread
 pub async fn list_subthread_by_id(
     State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
     Path(id): Path<i64>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
-    let thread =
-        state.thread_service.list_subthread_by_parent_id(id, cursor, limit).await?;
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Subthread,
+    )?;
+    let thread = state
+        .thread_service
+        .list_subthread_by_parent_id(id, cursor, limit, viewer_id)
+        .await?;
     Ok(Json(SuccessResponse::new("Success to fetch thread", Some(thread))))
 }
 
+// GET api/thread/{id}/revisions
+pub async fn list_thread_revision(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(params): Query<RequestCursorParmas>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Revisions,
+    )?;
+    let revisions = state.thread_service.list_thread_revision(id, cursor, limit).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch thread revisions", Some(revisions))))
+}
+
+// GET api/thread/{id}/context
+pub async fn get_thread_context(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+    let context = state.thread_service.get_thread_context(id, viewer_id).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch thread context", Some(context))))
+}
+
+// GET api/thread/{id}/tree
+pub async fn get_thread_tree(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Path(id): Path<i64>,
+    Query(params): Query<RequestThreadTreeParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+
+    let (cursor, _) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        None,
+        CursorKind::Replies,
+    )?;
+    let tree = state
+        .thread_service
+        .get_thread_tree(id, cursor, params.max_depth, params.per_level, viewer_id)
+        .await?;
+    Ok(Json(SuccessResponse::new("Success to fetch thread tree", Some(tree))))
+}
+
 // GET api/thread/feed/guest
 pub async fn list_guest_feed_thread(
     State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
-    let guest_thread_list =
-        state.thread_service.list_recommend_thread(None, cursor, limit).await?;
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Feed,
+    )?;
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+    let guest_thread_list = state
+        .thread_service
+        .list_recommend_thread(None, cursor, limit, viewer_id)
+        .await?;
     Ok(Json(SuccessResponse::new(
         "Success to fetch thread list",
         Some(guest_thread_list),
     )))
 }
 
+// GET api/thread/feed/popular
+pub async fn list_popular_thread(
+    State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
+    Query(params): Query<RequestCursorParmas>,
+) -> Result<impl IntoResponse, CustomError> {
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
+
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Popular,
+    )?;
+    let popular_thread_list =
+        state.thread_service.list_popular_thread(cursor, limit, viewer_id).await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch thread list",
+        Some(popular_thread_list),
+    )))
+}
+
 // GET api/thread/feed/personal
 pub async fn list_personal_feed_thread(
     State(state): State<AppState>,
     Extension(token_context): Extension<JwtClaims>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Feed,
+    )?;
     let personal_thread_list = state
         .thread_service
-        .list_recommend_thread(Some(token_context.id), cursor, limit)
+        .list_recommend_thread(
+            Some(token_context.id),
+            cursor,
+            limit,
+            Some(token_context.id),
+        )
         .await?;
     Ok(Json(SuccessResponse::new(
         "Success to fetch thread list",
@@ -83,18 +183,22 @@ pub async fn list_personal_feed_thread(
     )))
 }
 
-// PUT api/thread/{id}
+// PATCH api/thread/{id}
 pub async fn update_thread(
     State(state): State<AppState>,
     Extension(token_context): Extension<JwtClaims>,
     Path(id): Path<i64>,
+    IfMatch(if_match): IfMatch,
     Json(update_thread_dto): Json<RequestUpdateThread>,
 ) -> Result<impl IntoResponse, CustomError> {
     let thread = state
         .thread_service
-        .update_thread_by_id(token_context.id, id, update_thread_dto)
+        .update_thread_by_id(token_context.id, id, update_thread_dto, if_match)
         .await?;
-    Ok(Json(SuccessResponse::new("Success to update thread", Some(thread))))
+    Ok((
+        [(header::ETAG, etag(thread.version))],
+        Json(SuccessResponse::new("Success to update thread", Some(thread))),
+    ))
 }
 
 // DELETE api/thread/{id}
@@ -103,6 +207,22 @@ pub async fn delete_thread(
     Extension(token_context): Extension<JwtClaims>,
     Path(id): Path<i64>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let _ok = state.thread_service.delete_thread_by_id(token_context.id, id).await?;
+    let _ok = state
+        .thread_service
+        .delete_thread_by_id(token_context.id, token_context.role, id)
+        .await?;
     Ok(Json(SuccessResponse::<String>::new("Success to delete thread", None)))
 }
+
+// POST api/thread/{id}/restore
+pub async fn restore_thread(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    let thread = state
+        .thread_service
+        .restore_thread_by_id(token_context.id, token_context.role, id)
+        .await?;
+    Ok(Json(SuccessResponse::new("Success to restore thread", Some(thread))))
+}
@@ -7,8 +7,11 @@ use axum::{
 use crate::{
     api::state::AppState,
     domain::{
-        dto::{user::RequestUpsertProfile, RequestCursorParmas, SuccessResponse},
-        model::jwt_claims::JwtClaims,
+        dto::{
+            user::{RequestChangePassword, RequestUpsertProfile},
+            RequestCursorParmas, SuccessResponse,
+        },
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
     },
     error::CustomError,
     utils,
@@ -42,6 +45,19 @@ pub async fn upsert_profile(
     }
 }
 
+// PUT api/user/me/password
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(password_dto): Json<RequestChangePassword>,
+) -> Result<impl IntoResponse, CustomError> {
+    state
+        .user_service
+        .change_password(token_context.id, token_context.sid, password_dto)
+        .await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to change password", None)))
+}
+
 // GET api/user/{handle}
 pub async fn get_user_by_handle(
     State(state): State<AppState>,
@@ -59,17 +75,63 @@ pub async fn get_user_by_handle(
 // GET api/user/{handle}/thread
 pub async fn list_thread_by_user_handle(
     State(state): State<AppState>,
+    token_context: Option<Extension<JwtClaims>>,
     Path(user_handle): Path<String>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::UserThread,
+    )?;
+    let viewer_id = token_context.map(|Extension(claims)| claims.id);
     let user_thread_list = state
         .thread_service
-        .list_thread_by_user_handle(&user_handle, cursor, limit)
+        .list_thread_by_user_handle(&user_handle, cursor, limit, viewer_id)
         .await?;
     Ok(Json(SuccessResponse::new(
         "Success to fetch user based thread list",
         Some(user_thread_list),
     )))
 }
+
+// GET api/user/me/mentions
+pub async fn list_mentioned_thread(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Query(params): Query<RequestCursorParmas>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Mention,
+    )?;
+    let thread_list = state
+        .thread_service
+        .list_mentioned_thread(token_context.id, cursor, limit)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch mentioned thread list",
+        Some(thread_list),
+    )))
+}
+
+// GET api/user/me/sessions
+pub async fn list_session(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let session_list =
+        state.user_service.list_session(token_context.id, token_context.sid).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch session list", Some(session_list))))
+}
+
+// DELETE api/user/me/sessions/{id}
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.user_service.revoke_session(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to revoke session", None)))
+}
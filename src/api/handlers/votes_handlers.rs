@@ -8,7 +8,7 @@ use crate::{
     api::state::AppState,
     domain::{
         dto::{RequestCursorParmas, SuccessResponse},
-        model::{jwt_claims::JwtClaims, votes::ReactionType},
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims, votes::ReactionType},
     },
     error::CustomError,
     utils,
@@ -56,8 +56,11 @@ pub async fn list_upvoted_thread(
     Extension(token_context): Extension<JwtClaims>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Upvoted,
+    )?;
     match state.thread_service.list_upvoted_thread(token_context.id, cursor, limit).await
     {
         Ok(upvoted_threads) => Ok(Json(SuccessResponse::new(
@@ -74,8 +77,11 @@ pub async fn list_downvoted_thread(
     Extension(token_context): Extension<JwtClaims>,
     Query(params): Query<RequestCursorParmas>,
 ) -> Result<impl IntoResponse, CustomError> {
-    let (cursor, limit) =
-        utils::cursor::preprocessing_cursor(params.cursor.as_deref(), params.limit);
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::Downvoted,
+    )?;
     match state
         .thread_service
         .list_downvoted_thread(token_context.id, cursor, limit)
@@ -100,7 +106,7 @@ async fn vote_thread(
         ReactionType::Down => "Successfully downvoted the thread",
     };
 
-    let _ok = state.votes_service.react(token_context.id, id, reaction).await?;
+    state.votes_service.react(token_context.id, id, reaction).await?;
     Ok(Json(SuccessResponse::<String>::new(message, None)))
 }
 
@@ -115,6 +121,6 @@ async fn cancel_vote_thread(
         ReactionType::Down => "Successfully canceled downvote",
     };
 
-    let _ok = state.votes_service.react_cancel(token_context.id, id, reaction).await?;
+    state.votes_service.react_cancel(token_context.id, id, reaction).await?;
     Ok(Json(SuccessResponse::<String>::new(message, None)))
 }
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use crate::{
+    api::state::AppState,
+    domain::{
+        dto::{
+            webhook::{
+                RequestCreateWebhook, RequestUpdateWebhook, RequestWebhookDeliveryParams,
+            },
+            SuccessResponse,
+        },
+        model::{cursor_claims::CursorKind, jwt_claims::JwtClaims},
+    },
+    error::CustomError,
+    utils,
+};
+
+// POST api/user/me/webhooks
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Json(webhook_dto): Json<RequestCreateWebhook>,
+) -> Result<impl IntoResponse, CustomError> {
+    let webhook = state
+        .webhook_service
+        .create_webhook(token_context.id, token_context.role, webhook_dto)
+        .await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to create webhook. Copy the secret now, it will not be shown again.",
+        Some(webhook),
+    )))
+}
+
+// GET api/user/me/webhooks
+pub async fn list_webhook(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+) -> Result<impl IntoResponse, CustomError> {
+    let webhooks = state.webhook_service.list_webhook(token_context.id).await?;
+    Ok(Json(SuccessResponse::new("Success to fetch webhook list", Some(webhooks))))
+}
+
+// PUT api/user/me/webhooks/{id}
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+    Json(webhook_dto): Json<RequestUpdateWebhook>,
+) -> Result<impl IntoResponse, CustomError> {
+    let webhook =
+        state.webhook_service.update_webhook(token_context.id, id, webhook_dto).await?;
+    Ok(Json(SuccessResponse::new("Success to update webhook", Some(webhook))))
+}
+
+// DELETE api/user/me/webhooks/{id}
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, CustomError> {
+    state.webhook_service.delete_webhook(token_context.id, id).await?;
+    Ok(Json(SuccessResponse::<String>::new("Success to delete webhook", None)))
+}
+
+// GET api/user/me/webhooks/{id}/deliveries
+pub async fn list_webhook_delivery(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path(id): Path<i64>,
+    Query(params): Query<RequestWebhookDeliveryParams>,
+) -> Result<impl IntoResponse, CustomError> {
+    let (cursor, limit) = utils::cursor::preprocessing_cursor(
+        params.cursor.as_deref(),
+        params.limit,
+        CursorKind::WebhookDelivery,
+    )?;
+    let deliveries =
+        state.webhook_service.list_delivery(token_context.id, id, cursor, limit).await?;
+    Ok(Json(SuccessResponse::new(
+        "Success to fetch webhook deliveries",
+        Some(deliveries),
+    )))
+}
+
+// POST api/user/me/webhooks/{id}/deliveries/{delivery_id}/redeliver
+pub async fn redeliver_webhook(
+    State(state): State<AppState>,
+    Extension(token_context): Extension<JwtClaims>,
+    Path((id, delivery_id)): Path<(i64, i64)>,
+) -> Result<impl IntoResponse, CustomError> {
+    let delivery =
+        state.webhook_service.redeliver(token_context.id, id, delivery_id).await?;
+    Ok(Json(SuccessResponse::new("Success to queue webhook redelivery", Some(delivery))))
+}
@@ -1,8 +1,22 @@
-use crate::{domain::model::jwt_claims::JwtClaims, error::CustomError};
-use axum::{body::Body, http::Request, middleware::Next, response::IntoResponse};
+use crate::{
+    api::state::AppState,
+    domain::model::{access_token::ACCESS_TOKEN_PREFIX, jwt_claims::JwtClaims},
+    error::CustomError,
+};
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Request},
+    middleware::Next,
+    response::IntoResponse,
+};
 use tracing::error;
 
-pub async fn mw_require_auth(mut req: Request<Body>, next: Next) -> impl IntoResponse {
+pub async fn mw_require_auth(
+    State(state): State<AppState>,
+    mut req: Request<Body>,
+    next: Next,
+) -> impl IntoResponse {
     let auth_header = req.headers().get("Authorization");
     if auth_header.is_none() {
         return CustomError::PermissionDenied(
@@ -29,15 +43,42 @@ pub async fn mw_require_auth(mut req: Request<Body>, next: Next) -> impl IntoRes
     }
 
     let token = &auth_str[7..];
+    if token.starts_with(ACCESS_TOKEN_PREFIX) {
+        return match state.access_token_service.verify_access_token(token).await {
+            Ok(payload) => {
+                req.extensions_mut().insert(payload);
+                next.run(req).await
+            }
+            Err(err) => err.into_response(),
+        };
+    }
+
     match JwtClaims::decode_jwt(token) {
         Ok(payload) => {
+            if let Err(err) = state.user_service.verify_session(&payload).await {
+                return err.into_response();
+            }
             req.extensions_mut().insert(payload);
-            return next.run(req).await;
+            next.run(req).await
         }
         Err(err) => {
             error!("JWT decode error: {}", err);
-            return CustomError::Unauthorized("Invalid or expired token".to_string())
-                .into_response();
+            CustomError::Unauthorized("Invalid or expired token".to_string())
+                .into_response()
         }
     }
 }
+
+// Same as `mw_require_auth` when a token is sent, requests without one go through
+// as guests with no `JwtClaims` extension. Signed in users get personalized reads,
+// e.g. which threads they bookmarked or the users they follow ranked first.
+pub async fn mw_optional_auth(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> impl IntoResponse {
+    if req.headers().get(header::AUTHORIZATION).is_none() {
+        return next.run(req).await;
+    }
+    mw_require_auth(State(state), req, next).await.into_response()
+}
@@ -1,2 +1,4 @@
 pub mod auth_middleware;
 pub mod log_middleware;
+pub mod role_middleware;
+pub mod scope_middleware;
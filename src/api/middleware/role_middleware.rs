@@ -0,0 +1,30 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    domain::model::{jwt_claims::JwtClaims, user::UserRole},
+    error::CustomError,
+};
+
+// Requires at least the given role. Must be layered inside `mw_require_auth`.
+pub async fn mw_require_role(
+    State(role): State<UserRole>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    match req.extensions().get::<JwtClaims>() {
+        Some(token_context) if token_context.role >= role => next.run(req).await,
+        Some(_) => CustomError::PermissionDenied(format!(
+            "This endpoint requires the '{}' role",
+            role.as_str()
+        ))
+        .into_response(),
+        None => CustomError::Unauthorized("Authentication required".to_string())
+            .into_response(),
+    }
+}
@@ -0,0 +1,42 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    domain::model::{access_token::TokenScope, jwt_claims::JwtClaims},
+    error::CustomError,
+};
+
+// Must be layered inside `mw_require_auth`, which provides the token context.
+pub async fn mw_require_scope(
+    State(scope): State<TokenScope>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    match req.extensions().get::<JwtClaims>() {
+        Some(token_context) if token_context.has_scope(scope) => next.run(req).await,
+        Some(_) => {
+            CustomError::InsufficientScope(scope.as_str().to_string()).into_response()
+        }
+        None => CustomError::Unauthorized("Authentication required".to_string())
+            .into_response(),
+    }
+}
+
+// Account security endpoints (password, sessions, 2FA, tokens) are not available
+// to personal access tokens, whatever their scopes are.
+pub async fn mw_require_session(req: Request<Body>, next: Next) -> Response {
+    match req.extensions().get::<JwtClaims>() {
+        Some(token_context) if !token_context.is_access_token() => next.run(req).await,
+        Some(_) => CustomError::PermissionDenied(
+            "Personal access tokens cannot be used for this endpoint".to_string(),
+        )
+        .into_response(),
+        None => CustomError::Unauthorized("Authentication required".to_string())
+            .into_response(),
+    }
+}
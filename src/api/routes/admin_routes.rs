@@ -0,0 +1,27 @@
+use axum::{
+    middleware,
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::{
+    api::handlers::admin_handlers::{
+        list_user, suspend_user, unsuspend_user, update_user_role,
+    },
+    api::middleware::{
+        auth_middleware::mw_require_auth, role_middleware::mw_require_role,
+        scope_middleware::mw_require_session,
+    },
+    api::state::AppState,
+    domain::model::user::UserRole,
+};
+
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/users", get(list_user))
+        .route("/users/{id}/role", put(update_user_role))
+        .route("/users/{id}/suspend", post(suspend_user).delete(unsuspend_user))
+        .route_layer(middleware::from_fn_with_state(UserRole::Admin, mw_require_role))
+        .route_layer(middleware::from_fn(mw_require_session))
+        .layer(middleware::from_fn_with_state(state, mw_require_auth))
+}
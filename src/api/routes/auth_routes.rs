@@ -1,17 +1,35 @@
 use axum::{middleware, routing::post, Router};
 
 use crate::{
-    api::handlers::auth_handlers::{signin, signup},
-    api::middleware::auth_middleware::mw_require_auth,
+    api::handlers::auth_handlers::{
+        confirm_two_factor, disable_two_factor, enroll_two_factor, forgot_password,
+        refresh, resend_verification_email, reset_password, signin, signin_two_factor,
+        signout, signup, verify_email,
+    },
+    api::middleware::{
+        auth_middleware::mw_require_auth, scope_middleware::mw_require_session,
+    },
     api::state::AppState,
 };
 
-pub fn routes() -> Router<AppState> {
-    let accessible_router =
-        Router::new().route("/signup", post(signup)).route("/signin", post(signin));
+pub fn routes(state: AppState) -> Router<AppState> {
+    let accessible_router = Router::new()
+        .route("/signup", post(signup))
+        .route("/signin", post(signin))
+        .route("/signin/2fa", post(signin_two_factor))
+        .route("/refresh", post(refresh))
+        .route("/signout", post(signout))
+        .route("/password/forgot", post(forgot_password))
+        .route("/password/reset", post(reset_password))
+        .route("/verify-email", post(verify_email));
 
-    let restricted_router = Router::new().layer(middleware::from_fn(mw_require_auth));
+    let restricted_router = Router::new()
+        .route("/verify-email/resend", post(resend_verification_email))
+        .route("/2fa/enroll", post(enroll_two_factor))
+        .route("/2fa/confirm", post(confirm_two_factor))
+        .route("/2fa/disable", post(disable_two_factor))
+        .route_layer(middleware::from_fn(mw_require_session))
+        .layer(middleware::from_fn_with_state(state, mw_require_auth));
 
-    let routes = accessible_router.merge(restricted_router);
-    routes
+    accessible_router.merge(restricted_router)
 }
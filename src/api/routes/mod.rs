@@ -1,3 +1,7 @@
+pub mod admin_routes;
 pub mod auth_routes;
+pub mod search_routes;
+pub mod stream_routes;
+pub mod tag_routes;
 pub mod thread_routes;
 pub mod user_routes;
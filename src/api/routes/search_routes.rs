@@ -0,0 +1,14 @@
+use axum::{middleware, routing::get, Router};
+
+use crate::{
+    api::handlers::search_handlers::{search_thread, search_user},
+    api::middleware::auth_middleware::mw_optional_auth,
+    api::state::AppState,
+};
+
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/threads", get(search_thread))
+        .route("/users", get(search_user))
+        .layer(middleware::from_fn_with_state(state, mw_optional_auth))
+}
@@ -0,0 +1,20 @@
+use axum::{middleware, routing::get, Router};
+
+use crate::{
+    api::handlers::stream_handlers::stream,
+    api::middleware::{
+        auth_middleware::mw_require_auth, scope_middleware::mw_require_scope,
+    },
+    api::state::AppState,
+    domain::model::access_token::TokenScope,
+};
+
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(stream))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::FeedRead,
+            mw_require_scope,
+        ))
+        .layer(middleware::from_fn_with_state(state, mw_require_auth))
+}
@@ -0,0 +1,14 @@
+use axum::{middleware, routing::get, Router};
+
+use crate::{
+    api::handlers::tag_handlers::{list_thread_by_tag, list_trending_tag},
+    api::middleware::auth_middleware::mw_optional_auth,
+    api::state::AppState,
+};
+
+pub fn routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/trending", get(list_trending_tag))
+        .route("/{tag}/threads", get(list_thread_by_tag))
+        .layer(middleware::from_fn_with_state(state, mw_optional_auth))
+}
@@ -1,37 +1,78 @@
 use axum::{
     middleware,
-    routing::{get, post, put},
+    routing::{get, patch, post},
     Router,
 };
 
 use crate::{
     api::handlers::{
+        bookmark_handlers::{bookmark_thread, unbookmark_thread},
         thread_handlers::{
-            create_thread, delete_thread, get_thread_by_id, list_guest_feed_thread,
-            list_personal_feed_thread, list_subthread_by_id, update_thread,
+            create_thread, delete_thread, get_thread_by_id, get_thread_context,
+            get_thread_tree, list_guest_feed_thread, list_personal_feed_thread,
+            list_popular_thread, list_subthread_by_id, list_thread_revision,
+            restore_thread, update_thread,
         },
         votes_handlers::{
             cancel_downvote_thread, cancel_upvote_thread, downvote_thread, upvote_thread,
         },
     },
-    api::middleware::auth_middleware::mw_require_auth,
+    api::middleware::{
+        auth_middleware::{mw_optional_auth, mw_require_auth},
+        scope_middleware::mw_require_scope,
+    },
     api::state::AppState,
+    domain::model::access_token::TokenScope,
 };
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
     let accessible_router = Router::new()
         .route("/feed/guest", get(list_guest_feed_thread))
+        .route("/feed/popular", get(list_popular_thread))
         .route("/{id}", get(get_thread_by_id))
-        .route("/{id}/subthread", get(list_subthread_by_id));
+        .route("/{id}/subthread", get(list_subthread_by_id))
+        .route("/{id}/tree", get(get_thread_tree))
+        .route("/{id}/context", get(get_thread_context))
+        .route("/{id}/revisions", get(list_thread_revision))
+        .layer(middleware::from_fn_with_state(state.clone(), mw_optional_auth));
 
-    let restricted_router = Router::new()
+    let threads_write_router = Router::new()
         .route("/", post(create_thread))
+        .route("/{id}", patch(update_thread).delete(delete_thread))
+        .route("/{id}/restore", post(restore_thread))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::ThreadsWrite,
+            mw_require_scope,
+        ));
+
+    let feed_read_router = Router::new()
         .route("/feed/personal", get(list_personal_feed_thread))
-        .route("/{id}", put(update_thread).delete(delete_thread))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::FeedRead,
+            mw_require_scope,
+        ));
+
+    let votes_write_router = Router::new()
         .route("/{id}/up", post(upvote_thread).delete(cancel_upvote_thread))
         .route("/{id}/down", post(downvote_thread).delete(cancel_downvote_thread))
-        .layer(middleware::from_fn(mw_require_auth));
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::VotesWrite,
+            mw_require_scope,
+        ));
+
+    let bookmarks_write_router = Router::new()
+        .route("/{id}/bookmark", post(bookmark_thread).delete(unbookmark_thread))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::ProfileWrite,
+            mw_require_scope,
+        ));
+
+    let restricted_router = Router::new()
+        .merge(threads_write_router)
+        .merge(feed_read_router)
+        .merge(votes_write_router)
+        .merge(bookmarks_write_router)
+        .layer(middleware::from_fn_with_state(state, mw_require_auth));
 
-    let routes = accessible_router.merge(restricted_router);
-    routes
+    accessible_router.merge(restricted_router)
 }
@@ -1,36 +1,109 @@
 use axum::{
     middleware,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
 use crate::{
     api::handlers::{
+        access_token_handlers::{
+            create_access_token, list_access_token, revoke_access_token,
+        },
+        bookmark_handlers::{
+            create_bookmark_folder, delete_bookmark_folder, list_bookmark_folder,
+            list_bookmarked_thread, move_bookmark, rename_bookmark_folder,
+        },
         follow_handlers::{follow, list_user_follower, list_user_following, unfollow},
+        notification_handlers::{
+            count_unread_notification, get_notification_preferences, list_notification,
+            mark_all_notification_read, mark_notification_read,
+            update_notification_preferences,
+        },
         user_handlers::{
-            get_user_by_handle, list_thread_by_user_handle, me, upsert_profile,
+            change_password, get_user_by_handle, list_mentioned_thread, list_session,
+            list_thread_by_user_handle, me, revoke_session, upsert_profile,
         },
         votes_handlers::{list_downvoted_thread, list_upvoted_thread},
+        webhook_handlers::{
+            create_webhook, delete_webhook, list_webhook, list_webhook_delivery,
+            redeliver_webhook, update_webhook,
+        },
+    },
+    api::middleware::{
+        auth_middleware::{mw_optional_auth, mw_require_auth},
+        scope_middleware::{mw_require_scope, mw_require_session},
     },
-    api::middleware::auth_middleware::mw_require_auth,
     api::state::AppState,
+    domain::model::access_token::TokenScope,
 };
 
-pub fn routes() -> Router<AppState> {
+pub fn routes(state: AppState) -> Router<AppState> {
     let accessible_router = Router::new()
         .route("/{handle}", get(get_user_by_handle))
         .route("/{handle}/thread", get(list_thread_by_user_handle))
         .route("/{handle}/followers", get(list_user_follower))
-        .route("/{handle}/following", get(list_user_following));
+        .route("/{handle}/following", get(list_user_following))
+        .layer(middleware::from_fn_with_state(state.clone(), mw_optional_auth));
 
-    let restricted_router = Router::new()
+    let profile_read_router = Router::new()
         .route("/me", get(me))
-        .route("/me/profile", put(upsert_profile))
+        .route("/me/mentions", get(list_mentioned_thread))
+        .route("/me/bookmarks", get(list_bookmarked_thread))
+        .route("/me/bookmarks/folders", get(list_bookmark_folder))
+        .route("/me/notifications", get(list_notification))
+        .route("/me/notifications/unread-count", get(count_unread_notification))
+        .route("/me/notifications/preferences", get(get_notification_preferences))
         .route("/me/thread/upvoted", get(list_upvoted_thread))
         .route("/me/thread/downvoted", get(list_downvoted_thread))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::ProfileRead,
+            mw_require_scope,
+        ));
+
+    let profile_write_router = Router::new()
+        .route("/me/profile", put(upsert_profile))
+        .route("/me/bookmarks/{thread_id}", put(move_bookmark))
+        .route("/me/bookmarks/folders", post(create_bookmark_folder))
+        .route(
+            "/me/bookmarks/folders/{id}",
+            put(rename_bookmark_folder).delete(delete_bookmark_folder),
+        )
+        .route("/me/notifications/read", post(mark_all_notification_read))
+        .route("/me/notifications/{id}/read", post(mark_notification_read))
+        .route("/me/notifications/preferences", put(update_notification_preferences))
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::ProfileWrite,
+            mw_require_scope,
+        ));
+
+    let follows_write_router = Router::new()
         .route("/{target_user_handle}/follow", delete(unfollow).post(follow))
-        .layer(middleware::from_fn(mw_require_auth));
+        .route_layer(middleware::from_fn_with_state(
+            TokenScope::FollowsWrite,
+            mw_require_scope,
+        ));
+
+    let session_only_router = Router::new()
+        .route("/me/password", put(change_password))
+        .route("/me/sessions", get(list_session))
+        .route("/me/sessions/{id}", delete(revoke_session))
+        .route("/me/tokens", get(list_access_token).post(create_access_token))
+        .route("/me/tokens/{id}", delete(revoke_access_token))
+        .route("/me/webhooks", get(list_webhook).post(create_webhook))
+        .route("/me/webhooks/{id}", put(update_webhook).delete(delete_webhook))
+        .route("/me/webhooks/{id}/deliveries", get(list_webhook_delivery))
+        .route(
+            "/me/webhooks/{id}/deliveries/{delivery_id}/redeliver",
+            post(redeliver_webhook),
+        )
+        .route_layer(middleware::from_fn(mw_require_session));
+
+    let restricted_router = Router::new()
+        .merge(profile_read_router)
+        .merge(profile_write_router)
+        .merge(follows_write_router)
+        .merge(session_only_router)
+        .layer(middleware::from_fn_with_state(state, mw_require_auth));
 
-    let routes = accessible_router.merge(restricted_router);
-    routes
+    accessible_router.merge(restricted_router)
 }
@@ -1,7 +1,7 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware,
     response::{IntoResponse, Json},
     routing::get,
@@ -9,25 +9,50 @@ use axum::{
 };
 use serde_json::json;
 use sqlx::PgPool;
-use tracing::error;
+use tracing::{error, info};
 
 use crate::{
     api::middleware::log_middleware::mw_logging_request,
-    api::routes::{auth_routes, thread_routes, user_routes},
+    api::routes::{
+        admin_routes, auth_routes, search_routes, stream_routes, tag_routes,
+        thread_routes, user_routes,
+    },
     api::state::AppState,
+    config,
     domain::dto::ErrorResponse,
+    events::EventBus,
+    mailer::outbox_mailer::OutboxMailer,
     repository::{
-        follow_repo::FollowRepository, thread_repo::ThreadRepository,
-        user_repo::UserRepository, views_repo::ViewsRepository,
+        access_token_repo::AccessTokenRepository,
+        bookmark_repo::BookmarkRepository,
+        follow_repo::FollowRepository,
+        login_attempt_repo::LoginAttemptRepository,
+        login_throttle_repo::{
+            InMemoryLoginThrottleRepository, LoginThrottleRepository,
+            LoginThrottleRepositoryTrait,
+        },
+        notification_repo::NotificationRepository,
+        session_repo::SessionRepository,
+        thread_repo::ThreadRepository,
+        two_factor_repo::TwoFactorRepository,
+        user_repo::UserRepository,
+        user_token_repo::UserTokenRepository,
+        views_repo::ViewsRepository,
         votes_repo::VotesRepository,
+        webhook_repo::WebhookRepository,
     },
     services::{
-        follow_service::FollowService, thread_service::ThreadService,
+        access_token_service::AccessTokenService, admin_service::AdminService,
+        bookmark_service::BookmarkService, follow_service::FollowService,
+        login_guard_service::LoginGuardService,
+        notification_service::NotificationService, thread_service::ThreadService,
         user_service::UserService, votes_service::VotesService,
+        webhook_service::WebhookService,
     },
+    utils::jwt_keys,
 };
 
-fn di(db_pool: &PgPool) -> AppState {
+pub fn di(db_pool: &PgPool) -> AppState {
     let db_pool = Arc::new(db_pool.clone());
 
     let user_repo = Arc::new(UserRepository::new(Arc::clone(&db_pool)));
@@ -35,43 +60,153 @@ fn di(db_pool: &PgPool) -> AppState {
     let follow_repo = Arc::new(FollowRepository::new(Arc::clone(&db_pool)));
     let votes_repo = Arc::new(VotesRepository::new(Arc::clone(&db_pool)));
     let views_repo = Arc::new(ViewsRepository::new(Arc::clone(&db_pool)));
+    let session_repo = Arc::new(SessionRepository::new(Arc::clone(&db_pool)));
+    let user_token_repo = Arc::new(UserTokenRepository::new(Arc::clone(&db_pool)));
+    let two_factor_repo = Arc::new(TwoFactorRepository::new(Arc::clone(&db_pool)));
+    let access_token_repo = Arc::new(AccessTokenRepository::new(Arc::clone(&db_pool)));
+    let login_attempt_repo = Arc::new(LoginAttemptRepository::new(Arc::clone(&db_pool)));
+    let notification_repo = Arc::new(NotificationRepository::new(Arc::clone(&db_pool)));
+    let webhook_repo = Arc::new(WebhookRepository::new(Arc::clone(&db_pool)));
+    let bookmark_repo = Arc::new(BookmarkRepository::new(Arc::clone(&db_pool)));
+    // `memory` keeps the failure counters in this process, `postgres` shares them
+    let login_throttle_repo: Arc<dyn LoginThrottleRepositoryTrait> =
+        match config::env::envs().login_attempt_store.as_str() {
+            "memory" => Arc::new(InMemoryLoginThrottleRepository::new()),
+            "postgres" => Arc::new(LoginThrottleRepository::new(Arc::clone(&db_pool))),
+            store => panic!(
+                "unknown LOGIN_ATTEMPT_STORE `{}`, expected `memory` or `postgres`",
+                store
+            ),
+        };
+
+    let mailer = Arc::new(OutboxMailer::new(
+        &config::env::envs().mail_from,
+        &config::env::envs().mail_outbox_dir,
+    ));
 
-    let user_service = Arc::new(UserService::new(user_repo.clone(), follow_repo.clone()));
+    let login_guard =
+        Arc::new(LoginGuardService::new(login_throttle_repo, login_attempt_repo));
+    let user_service = Arc::new(UserService::new(
+        user_repo.clone(),
+        follow_repo.clone(),
+        session_repo.clone(),
+        user_token_repo.clone(),
+        two_factor_repo.clone(),
+        login_guard.clone(),
+        mailer.clone(),
+    ));
+    let event_bus = Arc::new(EventBus::new());
+    let notification_service =
+        Arc::new(NotificationService::new(notification_repo, event_bus.clone()));
+    let webhook_service = Arc::new(WebhookService::new(webhook_repo));
     let thread_service = Arc::new(ThreadService::new(
         user_repo.clone(),
         thread_repo.clone(),
         votes_repo.clone(),
         views_repo.clone(),
+        bookmark_repo.clone(),
+        notification_service.clone(),
+        webhook_service.clone(),
+        event_bus.clone(),
     ));
-    let follow_service =
-        Arc::new(FollowService::new(user_repo.clone(), follow_repo.clone()));
-    let votes_service =
-        Arc::new(VotesService::new(user_repo.clone(), thread_repo.clone(), votes_repo));
+    let follow_service = Arc::new(FollowService::new(
+        user_repo.clone(),
+        follow_repo.clone(),
+        notification_service.clone(),
+        webhook_service.clone(),
+    ));
+    let votes_service = Arc::new(VotesService::new(
+        user_repo.clone(),
+        thread_repo.clone(),
+        votes_repo,
+        notification_service.clone(),
+        webhook_service.clone(),
+        event_bus.clone(),
+    ));
+
+    let access_token_service =
+        Arc::new(AccessTokenService::new(user_repo.clone(), access_token_repo));
+    let admin_service =
+        Arc::new(AdminService::new(user_repo.clone(), session_repo.clone()));
+    let bookmark_service =
+        Arc::new(BookmarkService::new(thread_repo.clone(), bookmark_repo));
 
-    AppState { user_service, thread_service, follow_service, votes_service }
+    AppState {
+        user_service,
+        thread_service,
+        follow_service,
+        votes_service,
+        access_token_service,
+        admin_service,
+        bookmark_service,
+        notification_service,
+        webhook_service,
+        event_bus,
+    }
 }
 
 pub async fn routes_all(db_pool: &PgPool) -> Router {
     let app_state = di(db_pool);
+    spawn_thread_purge_job(Arc::clone(&app_state.thread_service));
+    spawn_webhook_delivery_job(Arc::clone(&app_state.webhook_service));
 
     let router_all = Router::new()
         .route("/ping", get(health_check_handler))
-        .nest("/auth", auth_routes::routes())
-        .nest("/user", user_routes::routes())
-        .nest("/thread", thread_routes::routes())
+        .nest("/auth", auth_routes::routes(app_state.clone()))
+        .nest("/user", user_routes::routes(app_state.clone()))
+        .nest("/thread", thread_routes::routes(app_state.clone()))
+        .nest("/admin", admin_routes::routes(app_state.clone()))
+        .nest("/search", search_routes::routes(app_state.clone()))
+        .nest("/tag", tag_routes::routes(app_state.clone()))
+        .nest("/stream", stream_routes::routes(app_state.clone()))
         .with_state(app_state);
 
-    let app = Router::new()
+    Router::new()
+        .route("/.well-known/jwks.json", get(jwks_handler))
         .nest("/api", router_all)
         .layer(middleware::from_fn(mw_logging_request))
-        .fallback(fallback_handler);
-    app
+        .fallback(fallback_handler)
+}
+
+// Hard-deletes the threads past the retention period in the background.
+fn spawn_thread_purge_job(thread_service: Arc<ThreadService>) {
+    let period = config::env::envs().thread_purge_interval_in_seconds.max(1) as u64;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(period));
+        loop {
+            interval.tick().await;
+            match thread_service.purge_deleted_thread().await {
+                Ok(0) => {}
+                Ok(purged) => info!("Purged {} deleted threads", purged),
+                Err(err) => error!("Failed to purge deleted threads: {:?}", err),
+            }
+        }
+    });
+}
+
+// Sends the queued webhook deliveries and their retries in the background.
+fn spawn_webhook_delivery_job(webhook_service: Arc<WebhookService>) {
+    let period = config::env::envs().webhook_delivery_interval_in_seconds.max(1) as u64;
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(period));
+        loop {
+            interval.tick().await;
+            if let Err(err) = webhook_service.deliver_due_webhook().await {
+                error!("Failed to deliver webhooks: {:?}", err);
+            }
+        }
+    });
 }
 
 async fn health_check_handler() -> impl IntoResponse {
     Json(json!({"message": "pong"}))
 }
 
+// Public keys for other services to verify our tokens.
+async fn jwks_handler() -> impl IntoResponse {
+    ([(header::CACHE_CONTROL, "public, max-age=300")], Json(jwt_keys::key_ring().jwks()))
+}
+
 async fn fallback_handler() -> impl IntoResponse {
     let status_code = StatusCode::NOT_FOUND;
     let message = "Handler Not Found";
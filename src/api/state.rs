@@ -1,8 +1,14 @@
 use std::sync::Arc;
 
-use crate::services::{
-    follow_service::FollowService, thread_service::ThreadService,
-    user_service::UserService, votes_service::VotesService,
+use crate::{
+    events::EventBus,
+    services::{
+        access_token_service::AccessTokenService, admin_service::AdminService,
+        bookmark_service::BookmarkService, follow_service::FollowService,
+        notification_service::NotificationService, thread_service::ThreadService,
+        user_service::UserService, votes_service::VotesService,
+        webhook_service::WebhookService,
+    },
 };
 
 #[derive(Clone)]
@@ -11,4 +17,10 @@ pub struct AppState {
     pub thread_service: Arc<ThreadService>,
     pub follow_service: Arc<FollowService>,
     pub votes_service: Arc<VotesService>,
+    pub access_token_service: Arc<AccessTokenService>,
+    pub admin_service: Arc<AdminService>,
+    pub bookmark_service: Arc<BookmarkService>,
+    pub notification_service: Arc<NotificationService>,
+    pub webhook_service: Arc<WebhookService>,
+    pub event_bus: Arc<EventBus>,
 }
@@ -1,6 +1,8 @@
 use dotenvy::dotenv;
 use std::sync::OnceLock;
 
+pub const DEFAULT_JWT_SECRET: &str = "tempSecret";
+
 pub fn envs() -> &'static Envs {
     static INSTANCE: OnceLock<Envs> = OnceLock::new();
     INSTANCE.get_or_init(|| {
@@ -11,28 +13,182 @@ pub fn envs() -> &'static Envs {
 
 #[derive(Debug)]
 pub struct Envs {
+    pub app_env: String,
     pub db_url: String,
     pub jwt_secret: String,
+    pub jwt_keys: String,
+    pub jwt_active_kid: String,
+    pub cursor_secret: String,
+    pub password_hash_algorithm: String,
+    pub bcrypt_cost: i64,
+    pub argon2_memory_kib: i64,
+    pub argon2_iterations: i64,
+    pub argon2_parallelism: i64,
     pub jwt_expiration_in_seconds: i64,
+    pub refresh_token_expiration_in_seconds: i64,
+    pub trust_proxy_headers: bool,
+    pub password_reset_expiration_in_seconds: i64,
+    pub email_verification_expiration_in_seconds: i64,
+    pub email_verification_resend_interval_in_seconds: i64,
+    pub require_verified_email_to_post: bool,
+    pub require_verified_email_to_vote: bool,
+    pub two_factor_challenge_expiration_in_seconds: i64,
+    pub totp_issuer: String,
+    pub login_attempt_store: String,
+    pub login_max_failures_per_account: i64,
+    pub login_max_failures_per_ip: i64,
+    pub login_failure_window_in_seconds: i64,
+    pub login_lockout_base_in_seconds: i64,
+    pub login_lockout_max_in_seconds: i64,
+    pub thread_edit_window_in_seconds: i64,
+    pub thread_restore_window_in_seconds: i64,
+    pub thread_retention_in_seconds: i64,
+    pub thread_purge_interval_in_seconds: i64,
+    pub thread_snapshot_size: i64,
+    pub thread_snapshot_ttl_in_seconds: i64,
+    pub thread_snapshot_bucket_in_seconds: i64,
+    pub tag_trending_window_in_seconds: i64,
+    pub webhook_delivery_interval_in_seconds: i64,
+    pub webhook_max_attempts: i64,
+    pub webhook_retry_base_in_seconds: i64,
+    pub app_base_url: String,
+    pub mail_from: String,
+    pub mail_outbox_dir: String,
 }
 
 impl Envs {
     pub fn new() -> Self {
         Self {
+            // the default secrets are only allowed when development is asked for
+            app_env: get_env("APP_ENV", "production"),
             db_url: get_env("DATABASE_URL", "sqlite:./sqlite.db"),
-            jwt_secret: get_env("JWT_SECRET", "tempSecret"),
+            jwt_secret: get_env("JWT_SECRET", DEFAULT_JWT_SECRET),
+            jwt_keys: get_env("JWT_KEYS", ""),
+            jwt_active_kid: get_env("JWT_ACTIVE_KID", ""),
+            cursor_secret: get_env("CURSOR_SECRET", ""),
+            password_hash_algorithm: get_env("PASSWORD_HASH_ALGORITHM", "argon2id"),
+            // recommended range: 12 <= cost <= 14
+            bcrypt_cost: get_env_as_int("BCRYPT_COST", 13),
+            // OWASP minimum for Argon2id: 19 MiB, 2 iterations, 1 degree of parallelism
+            argon2_memory_kib: get_env_as_int("ARGON2_MEMORY_KIB", 19 * 1024),
+            argon2_iterations: get_env_as_int("ARGON2_ITERATIONS", 2),
+            argon2_parallelism: get_env_as_int("ARGON2_PARALLELISM", 1),
             jwt_expiration_in_seconds: get_env_as_int(
                 "JWT_EXPIRATION_IN_SECONDS",
+                60 * 15,
+            ),
+            refresh_token_expiration_in_seconds: get_env_as_int(
+                "REFRESH_TOKEN_EXPIRATION_IN_SECONDS",
+                60 * 60 * 24 * 30,
+            ),
+            trust_proxy_headers: get_env_as_bool("TRUST_PROXY_HEADERS", false),
+            password_reset_expiration_in_seconds: get_env_as_int(
+                "PASSWORD_RESET_EXPIRATION_IN_SECONDS",
+                60 * 60,
+            ),
+            email_verification_expiration_in_seconds: get_env_as_int(
+                "EMAIL_VERIFICATION_EXPIRATION_IN_SECONDS",
+                60 * 60 * 24,
+            ),
+            email_verification_resend_interval_in_seconds: get_env_as_int(
+                "EMAIL_VERIFICATION_RESEND_INTERVAL_IN_SECONDS",
+                60,
+            ),
+            require_verified_email_to_post: get_env_as_bool(
+                "REQUIRE_VERIFIED_EMAIL_TO_POST",
+                true,
+            ),
+            require_verified_email_to_vote: get_env_as_bool(
+                "REQUIRE_VERIFIED_EMAIL_TO_VOTE",
+                true,
+            ),
+            two_factor_challenge_expiration_in_seconds: get_env_as_int(
+                "TWO_FACTOR_CHALLENGE_EXPIRATION_IN_SECONDS",
+                60 * 5,
+            ),
+            totp_issuer: get_env("TOTP_ISSUER", "Thread"),
+            login_attempt_store: get_env("LOGIN_ATTEMPT_STORE", "postgres"),
+            login_max_failures_per_account: get_env_as_int(
+                "LOGIN_MAX_FAILURES_PER_ACCOUNT",
+                5,
+            ),
+            login_max_failures_per_ip: get_env_as_int("LOGIN_MAX_FAILURES_PER_IP", 20),
+            login_failure_window_in_seconds: get_env_as_int(
+                "LOGIN_FAILURE_WINDOW_IN_SECONDS",
+                60 * 15,
+            ),
+            login_lockout_base_in_seconds: get_env_as_int(
+                "LOGIN_LOCKOUT_BASE_IN_SECONDS",
+                30,
+            ),
+            login_lockout_max_in_seconds: get_env_as_int(
+                "LOGIN_LOCKOUT_MAX_IN_SECONDS",
+                60 * 60,
+            ),
+            // 0 allows editing at any time
+            thread_edit_window_in_seconds: get_env_as_int(
+                "THREAD_EDIT_WINDOW_IN_SECONDS",
+                60 * 60,
+            ),
+            thread_restore_window_in_seconds: get_env_as_int(
+                "THREAD_RESTORE_WINDOW_IN_SECONDS",
                 60 * 60 * 24 * 7,
             ),
+            // deleted threads are hard-deleted after this, keep it above the restore window
+            thread_retention_in_seconds: get_env_as_int(
+                "THREAD_RETENTION_IN_SECONDS",
+                60 * 60 * 24 * 30,
+            ),
+            thread_purge_interval_in_seconds: get_env_as_int(
+                "THREAD_PURGE_INTERVAL_IN_SECONDS",
+                60 * 60,
+            ),
+            // threads ranked at a time, the next chunk is ranked when paging reaches it
+            thread_snapshot_size: get_env_as_int("THREAD_SNAPSHOT_SIZE", 200),
+            thread_snapshot_ttl_in_seconds: get_env_as_int(
+                "THREAD_SNAPSHOT_TTL_IN_SECONDS",
+                60 * 30,
+            ),
+            thread_snapshot_bucket_in_seconds: get_env_as_int(
+                "THREAD_SNAPSHOT_BUCKET_IN_SECONDS",
+                60,
+            ),
+            tag_trending_window_in_seconds: get_env_as_int(
+                "TAG_TRENDING_WINDOW_IN_SECONDS",
+                60 * 60 * 24,
+            ),
+            webhook_delivery_interval_in_seconds: get_env_as_int(
+                "WEBHOOK_DELIVERY_INTERVAL_IN_SECONDS",
+                5,
+            ),
+            webhook_max_attempts: get_env_as_int("WEBHOOK_MAX_ATTEMPTS", 8),
+            webhook_retry_base_in_seconds: get_env_as_int(
+                "WEBHOOK_RETRY_BASE_IN_SECONDS",
+                30,
+            ),
+            app_base_url: get_env("APP_BASE_URL", "http://localhost:8080"),
+            mail_from: get_env("MAIL_FROM", "no-reply@thread.local"),
+            mail_outbox_dir: get_env("MAIL_OUTBOX_DIR", "./outbox"),
         }
     }
+
+    pub fn is_development(&self) -> bool {
+        self.app_env == "development"
+    }
 }
 
+// Empty values (e.g. copied from `.env.example`) fall back like unset ones.
 fn get_env(key: &str, fallback: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| fallback.to_string())
+    std::env::var(key)
+        .ok()
+        .filter(|val| !val.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
 }
 
 fn get_env_as_int(key: &str, fallback: i64) -> i64 {
     std::env::var(key).ok().and_then(|val| val.parse().ok()).unwrap_or(fallback)
 }
+
+fn get_env_as_bool(key: &str, fallback: bool) -> bool {
+    std::env::var(key).ok().and_then(|val| val.parse().ok()).unwrap_or(fallback)
+}
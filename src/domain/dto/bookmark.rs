@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+// `folder_id` saves the thread straight into a folder.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestBookmarkThreadParams {
+    pub folder_id: Option<i64>,
+}
+
+// `None` takes the bookmark out of its folder.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestMoveBookmark {
+    pub folder_id: Option<i64>,
+}
+
+// Every bookmark when `folder_id` is left out.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestBookmarkListParams {
+    pub folder_id: Option<i64>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestBookmarkFolder {
+    pub name: String,
+}
@@ -1,8 +1,12 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+pub mod bookmark;
+pub mod notification;
+pub mod search;
 pub mod thread;
 pub mod user;
+pub mod webhook;
 
 // pub type ApiResponse<T> = Result<SuccessResponse<T>, ErrorResponse>;
 
@@ -34,6 +38,26 @@ pub struct RequestCursorParmas {
     pub limit: Option<i64>,
 }
 
+// `threads` is a comma separated list of the thread ids the client is showing.
+#[derive(Debug, Deserialize)]
+pub struct RequestStreamParams {
+    pub threads: Option<String>,
+}
+
+// One page of a list, `next_cursor` is passed back as `cursor` to fetch the next one.
+#[derive(Serialize)]
+pub struct ResponsePage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl<T> ResponsePage<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<String>) -> Self {
+        Self { items, has_more: next_cursor.is_some(), next_cursor }
+    }
+}
+
 impl<T> SuccessResponse<T> {
     pub fn new(message: &str, data: Option<T>) -> Self {
         Self {
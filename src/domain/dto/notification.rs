@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::thread::UserProfile;
+use crate::domain::model::notification::NotificationType;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestNotificationParams {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    pub unread_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseNotification {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub notification_type: NotificationType,
+    pub thread_id: Option<i64>,
+    // e.g. "12 people upvoted your thread"
+    pub message: String,
+    pub actor_count: i32,
+    // the latest actors first
+    pub actors: Vec<UserProfile>,
+    pub is_read: bool,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseUnreadNotificationCount {
+    pub unread_count: i64,
+}
+
+// Types left out keep their current setting.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NotificationPreferences {
+    pub follow: Option<bool>,
+    pub reply: Option<bool>,
+    pub upvote: Option<bool>,
+    pub mention: Option<bool>,
+}
+
+impl NotificationPreferences {
+    pub fn get(&self, notification_type: NotificationType) -> Option<bool> {
+        match notification_type {
+            NotificationType::Follow => self.follow,
+            NotificationType::Reply => self.reply,
+            NotificationType::Upvote => self.upvote,
+            NotificationType::Mention => self.mention,
+        }
+    }
+
+    pub fn set(&mut self, notification_type: NotificationType, enabled: bool) {
+        let preference = match notification_type {
+            NotificationType::Follow => &mut self.follow,
+            NotificationType::Reply => &mut self.reply,
+            NotificationType::Upvote => &mut self.upvote,
+            NotificationType::Mention => &mut self.mention,
+        };
+        *preference = Some(enabled);
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use super::thread::ResponseThreadWithUserProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadSearchType {
+    Root,
+    Reply,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestSearchThreadParams {
+    pub q: Option<String>,
+    // handle of the author, with or without the leading `@`
+    pub author: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(rename = "type")]
+    pub thread_type: Option<ThreadSearchType>,
+    pub min_votes: Option<i64>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+// The filters of a search, once validated.
+#[derive(Debug, Clone)]
+pub struct ThreadSearchFilter {
+    pub query: String,
+    pub author: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub thread_type: Option<ThreadSearchType>,
+    pub min_votes: Option<i64>,
+}
+
+// The matched terms of `title_highlight` and `snippet` are wrapped in `<mark>`,
+// the rest of the text is HTML-escaped.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseThreadSearchResult {
+    #[serde(flatten)]
+    pub thread: ResponseThreadWithUserProfile,
+    pub rank: f32,
+    pub title_highlight: Option<String>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestSearchUserParams {
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct ResponseUserSearchResult {
+    pub id: i64,
+    pub name: String,
+    pub handle: String,
+    pub profile_img_url: Option<String>,
+    pub follower_count: i64,
+    // whether the searcher follows this user, `false` for guests
+    pub is_following: bool,
+}
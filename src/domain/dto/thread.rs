@@ -9,11 +9,15 @@ pub struct RequestCreateThread {
     pub parent_thread: Option<i64>,
 }
 
+// Omitted fields are left unchanged. A thread cannot be moved, so `parent_thread`
+// is rejected as an unknown field. `version` (or `If-Match`) is the version the
+// edit was based on.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct RequestUpdateThread {
-    pub title: String,
-    pub content: String,
-    pub parent_thread: i64,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub version: Option<i32>,
 }
 
 #[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
@@ -27,6 +31,17 @@ pub struct ResponseThread {
     pub votes: i64,
     pub views: i64,
     pub reply_count: i64,
+    pub edited: bool,
+    pub edit_count: i32,
+    pub version: i32,
+    // filled by the service, not part of the thread row
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub mentions: Vec<MentionEntity>,
+    // whether the signed in viewer saved it, filled by the service
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub is_bookmarked: bool,
 
     pub is_deleted: bool,
     pub deleted_at: Option<DateTime<Utc>>,
@@ -34,6 +49,15 @@ pub struct ResponseThread {
     pub updated_at: DateTime<Utc>,
 }
 
+// A resolved `@handle` in the content, `start`..`end` are character offsets.
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct MentionEntity {
+    pub user_id: i64,
+    pub handle: String,
+    pub start: i32,
+    pub end: i32,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ResponseThreadWithUserProfile {
     pub id: i64,
@@ -41,11 +65,17 @@ pub struct ResponseThreadWithUserProfile {
     pub content: String,
     pub parent_thread: Option<i64>,
 
-    pub user_profile: UserProfile,
+    // `None` for a deleted thread, shown as a `[deleted]` tombstone
+    pub user_profile: Option<UserProfile>,
 
     pub votes: i64,
     pub views: i64,
     pub reply_count: i64,
+    pub edited: bool,
+    pub edit_count: i32,
+    pub version: i32,
+    pub mentions: Vec<MentionEntity>,
+    pub is_bookmarked: bool,
 
     pub is_deleted: bool,
     pub deleted_at: Option<DateTime<Utc>>,
@@ -59,3 +89,47 @@ pub struct UserProfile {
     pub handle: String,
     pub profile_img: String,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestTrendingTagParams {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestThreadTreeParams {
+    pub cursor: Option<String>,
+    pub max_depth: Option<i32>,
+    pub per_level: Option<i64>,
+}
+
+// A thread of `GET api/thread/{id}/tree` and the loaded part of its replies.
+// When `has_more_replies` is set, the remaining replies are fetched from
+// `GET api/thread/{id}/tree`, with `replies_cursor` when some were already loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseThreadTreeNode {
+    #[serde(flatten)]
+    pub thread: ResponseThreadWithUserProfile,
+    pub depth: i32,
+    pub replies: Vec<ResponseThreadTreeNode>,
+    pub has_more_replies: bool,
+    pub replies_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseThreadTree {
+    // from the root of the conversation to the parent of `thread`
+    pub ancestor_ids: Vec<i64>,
+    pub thread: ResponseThreadWithUserProfile,
+    pub replies: Vec<ResponseThreadTreeNode>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+// Deleted threads of the chain are `[deleted]` tombstones, as in lists and trees.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseThreadContext {
+    pub root: ResponseThreadWithUserProfile,
+    // from the root to the parent of `thread`
+    pub ancestors: Vec<ResponseThreadWithUserProfile>,
+    pub thread: ResponseThreadWithUserProfile,
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::domain::model::{access_token::TokenScope, user::UserRole};
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct RequestSignup {
     pub email: String,
@@ -18,6 +20,67 @@ pub struct RequestSignin {
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ResponseSignin {
     pub token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseTwoFactorChallenge {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ResponseSigninResult {
+    Authenticated(ResponseSignin),
+    TwoFactorRequired(ResponseTwoFactorChallenge),
+}
+
+// Either a TOTP `code` or one of the `recovery_code`s is required.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestTwoFactorSignin {
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseTwoFactorEnrollment {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestTwoFactorCode {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseRecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestDisableTwoFactor {
+    pub password: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestRefreshToken {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseSession {
+    pub id: i64,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub is_current: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
@@ -28,6 +91,8 @@ pub struct ResponseProfile {
     pub handle: String,
     pub profile_img_url: String,
     pub bio: Option<String>,
+    pub is_email_verified: bool,
+    pub role: UserRole,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub follower_count: i64,
@@ -41,3 +106,71 @@ pub struct RequestUpsertProfile {
     pub profile_img_url: String,
     pub bio: String,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestChangePassword {
+    pub current_password: String,
+    pub new_password: String,
+    pub new_password_confirm: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestForgotPassword {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestResetPassword {
+    pub token: String,
+    pub new_password: String,
+    pub new_password_confirm: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestVerifyEmail {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestCreateAccessToken {
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    // never expires when omitted
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseAccessToken {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// The plain `token` is only returned once, on creation.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResponseCreatedAccessToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub access_token: ResponseAccessToken,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RequestUpdateUserRole {
+    pub role: UserRole,
+}
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct ResponseAdminUser {
+    pub id: i64,
+    pub email: String,
+    pub handle: Option<String>,
+    pub role: UserRole,
+    pub is_email_verified: bool,
+    pub is_suspended: bool,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::model::webhook::{WebhookDeliveryStatus, WebhookEvent};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestCreateWebhook {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    // admins only, the events of every user instead of their own
+    pub all_users: Option<bool>,
+}
+
+// Fields left out are not changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestUpdateWebhook {
+    pub url: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestWebhookDeliveryParams {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseWebhook {
+    pub id: i64,
+    pub url: String,
+    pub events: Vec<String>,
+    pub all_users: bool,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// The `secret` is only returned once, on creation.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseCreatedWebhook {
+    pub secret: String,
+    #[serde(flatten)]
+    pub webhook: ResponseWebhook,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    // `None` once delivered or given up
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub redelivery_of: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
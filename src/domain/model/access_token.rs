@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Every personal access token starts with this, so leaked tokens are easy to spot.
+pub const ACCESS_TOKEN_PREFIX: &str = "thr_pat_";
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct PersonalAccessToken {
+    pub id: i64,
+    pub user_id: i64,
+
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TokenScope {
+    #[serde(rename = "threads:write")]
+    ThreadsWrite,
+    #[serde(rename = "feed:read")]
+    FeedRead,
+    #[serde(rename = "votes:write")]
+    VotesWrite,
+    #[serde(rename = "follows:write")]
+    FollowsWrite,
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+    #[serde(rename = "profile:write")]
+    ProfileWrite,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::ThreadsWrite => "threads:write",
+            TokenScope::FeedRead => "feed:read",
+            TokenScope::VotesWrite => "votes:write",
+            TokenScope::FollowsWrite => "follows:write",
+            TokenScope::ProfileRead => "profile:read",
+            TokenScope::ProfileWrite => "profile:write",
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::domain::dto::thread::ResponseThread;
+
+// A folder of the user, with how many threads are saved in it.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct BookmarkFolder {
+    pub id: i64,
+    pub name: String,
+    pub bookmark_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// A thread in the bookmark list, ordered by when the user saved it.
+#[derive(Debug, Clone, FromRow)]
+pub struct BookmarkedThread {
+    #[sqlx(flatten)]
+    pub thread: ResponseThread,
+    pub bookmarked_at: DateTime<Utc>,
+}
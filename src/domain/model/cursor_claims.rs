@@ -1,36 +1,77 @@
-use base64::{engine::general_purpose, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::str;
 
+pub const CURSOR_VERSION: u8 = 2;
+
+// The list a cursor was issued for, a cursor of one list is rejected by the others
+// since they are ordered by different keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorKind {
+    Subthread,
+    Replies,
+    Feed,
+    Popular,
+    Revisions,
+    UserThread,
+    Upvoted,
+    Downvoted,
+    Follower,
+    Following,
+    AdminUser,
+    Search,
+    Tag,
+    Mention,
+    Notification,
+    Bookmark,
+    WebhookDelivery,
+}
+
+// Position after the last item of a page.
+// - Time ordered lists: `sort_at` is the timestamp the list is ordered by
+//   (created_at, followed_at, reacted_at...) and `id` the tie breaker.
+// - Score ordered lists: `snapshot_id` is the snapshot the order was frozen in and
+//   `position` the rank of the last item.
+// - Search results: `rank` is the relevance of the last item and `id` the tie breaker.
+// The optional keys are `None` on the first page.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CursorClaims {
+    #[serde(rename = "v")]
+    pub version: u8,
+    pub kind: CursorKind,
     pub id: i64,
-    pub created_at: Option<DateTime<Utc>>,
+    pub sort_at: Option<DateTime<Utc>>,
+    pub snapshot_id: Option<i64>,
+    pub position: Option<i64>,
+    pub rank: Option<f32>,
 }
 
-impl Default for CursorClaims {
-    fn default() -> Self {
-        let created_default_str = Utc::now().to_rfc3339();
-        let created_at = DateTime::parse_from_rfc3339(&created_default_str)
-            .ok()
-            .map(|dt| dt.with_timezone(&Utc));
-        Self { id: 0, created_at }
+impl CursorClaims {
+    pub fn first_page(kind: CursorKind) -> Self {
+        Self {
+            version: CURSOR_VERSION,
+            kind,
+            id: 0,
+            sort_at: None,
+            snapshot_id: None,
+            position: None,
+            rank: None,
+        }
     }
-}
 
-impl CursorClaims {
-    pub fn decode_cursor(cursor: &str) -> Option<CursorClaims> {
-        let decoded_bytes = general_purpose::STANDARD.decode(cursor).ok()?;
-        let decoded_str = str::from_utf8(&decoded_bytes).ok()?;
-        let claims: serde_json::Value = serde_json::from_str(decoded_str).ok()?;
+    pub fn new(kind: CursorKind, sort_at: DateTime<Utc>, id: i64) -> Self {
+        Self { sort_at: Some(sort_at), id, ..Self::first_page(kind) }
+    }
+
+    pub fn by_rank(kind: CursorKind, rank: f32, id: i64) -> Self {
+        Self { rank: Some(rank), id, ..Self::first_page(kind) }
+    }
 
-        let id = claims.get("id").and_then(|v| v.as_i64()).unwrap_or_default();
-        let created_at = claims
-            .get("created_at")
-            .and_then(|v| v.as_str())
-            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-            .map(|dt| dt.with_timezone(&Utc));
-        Some(CursorClaims { id, created_at })
+    pub fn in_snapshot(kind: CursorKind, snapshot_id: i64, position: i64) -> Self {
+        Self {
+            snapshot_id: Some(snapshot_id),
+            position: Some(position),
+            ..Self::first_page(kind)
+        }
     }
 }
@@ -2,13 +2,6 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
-pub struct Follow {
-    pub user_id: i64,
-    pub follower_id: i64,
-    pub created_at: DateTime<Utc>,
-}
-
 #[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
 pub struct FollowList {
     pub id: i64,
@@ -1,11 +1,13 @@
-use chrono::{Duration, Utc};
-use jsonwebtoken::{
-    decode, encode, errors::Error, DecodingKey, EncodingKey, Header, Validation,
-};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::errors::Error;
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-use crate::config;
+use crate::{
+    config,
+    domain::model::{access_token::TokenScope, user::UserRole},
+    utils::jwt_keys,
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JwtClaims {
@@ -16,10 +18,24 @@ pub struct JwtClaims {
     // data
     pub id: i64,
     pub email: String,
+    #[serde(default)]
+    pub role: UserRole,
+    // session (refresh token family) the access token was issued for,
+    // `None` for personal access tokens
+    #[serde(default)]
+    pub sid: Option<i64>,
+    // `None` grants everything, personal access tokens are limited to their scopes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
 }
 
 impl JwtClaims {
-    pub fn new(user_id: i64, user_email: &str) -> Self {
+    pub fn new(
+        user_id: i64,
+        user_email: &str,
+        user_role: UserRole,
+        session_id: i64,
+    ) -> Self {
         let time_now = Utc::now();
         let issued_at = time_now.timestamp() as u64;
         let expiration = (time_now
@@ -31,29 +47,61 @@ impl JwtClaims {
             exp: expiration,
             id: user_id,
             email: user_email.to_string(),
+            role: user_role,
+            sid: Some(session_id),
+            scopes: None,
+        }
+    }
+
+    // Request context of a personal access token. It is never encoded as a JWT.
+    pub fn for_access_token(
+        user_id: i64,
+        user_email: &str,
+        user_role: UserRole,
+        scopes: Vec<String>,
+    ) -> Self {
+        let issued_at = Utc::now().timestamp() as u64;
+
+        JwtClaims {
+            iat: issued_at,
+            exp: issued_at,
+            id: user_id,
+            email: user_email.to_string(),
+            role: user_role,
+            sid: None,
+            scopes: Some(scopes),
         }
     }
 
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.iter().any(|granted| granted == scope.as_str()))
+    }
+
+    pub fn is_access_token(&self) -> bool {
+        self.scopes.is_some()
+    }
+
+    // `None` for personal access tokens, their expiry is checked against the store.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        if self.is_access_token() {
+            return None;
+        }
+        DateTime::from_timestamp(self.exp as i64, 0)
+    }
+
     pub fn encode_jwt(claims: JwtClaims) -> Result<String, Error> {
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(config::env::envs().jwt_secret.as_ref()),
-        )
-        .map_err(|err| {
+        jwt_keys::key_ring().encode(&claims).map_err(|err| {
             error!("Error encoding JWT: {}", err);
             err
         })
     }
 
     pub fn decode_jwt(token: &str) -> Result<JwtClaims, Error> {
-        let decoding_key =
-            DecodingKey::from_secret(config::env::envs().jwt_secret.as_ref());
-        decode::<JwtClaims>(token, &decoding_key, &Validation::default())
-            .map(|decoded_token| decoded_token.claims)
-            .map_err(|err| {
-                error!("Error decoding JWT: {}", err);
-                err
-            })
+        jwt_keys::key_ring().decode::<JwtClaims>(token).map_err(|err| {
+            error!("Error decoding JWT: {}", err);
+            err
+        })
     }
 }
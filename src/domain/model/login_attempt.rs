@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct LoginThrottle {
+    pub throttle_key: String,
+    pub failure_count: i32,
+
+    pub last_failed_at: DateTime<Utc>,
+    pub locked_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, sqlx::Type, Deserialize, Serialize)]
+#[sqlx(type_name = "login_failure_reason_enum", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LoginFailureReason {
+    #[serde(rename = "UNKNOWN_EMAIL")]
+    UnknownEmail,
+    #[serde(rename = "INVALID_PASSWORD")]
+    InvalidPassword,
+    #[serde(rename = "INVALID_TWO_FACTOR")]
+    InvalidTwoFactor,
+    #[serde(rename = "LOCKED_OUT")]
+    LockedOut,
+}
@@ -1,6 +1,15 @@
+pub mod access_token;
+pub mod bookmark;
 pub mod cursor_claims;
 pub mod follow;
 pub mod jwt_claims;
+pub mod login_attempt;
+pub mod notification;
+pub mod session;
 pub mod thread;
+pub mod two_factor;
+pub mod two_factor_claims;
 pub mod user;
+pub mod user_token;
 pub mod votes;
+pub mod webhook;
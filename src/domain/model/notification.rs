@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, sqlx::Type, Deserialize, Serialize)]
+#[sqlx(type_name = "notification_type_enum", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationType {
+    #[serde(rename = "FOLLOW")]
+    Follow,
+    #[serde(rename = "REPLY")]
+    Reply,
+    #[serde(rename = "UPVOTE")]
+    Upvote,
+    #[serde(rename = "MENTION")]
+    Mention,
+}
+
+impl NotificationType {
+    pub const ALL: [NotificationType; 4] = [
+        NotificationType::Follow,
+        NotificationType::Reply,
+        NotificationType::Upvote,
+        NotificationType::Mention,
+    ];
+}
+
+// A group of events of one type, `thread_id` is `None` for follows.
+#[derive(Debug, Clone, FromRow)]
+pub struct Notification {
+    pub id: i64,
+    #[sqlx(rename = "type")]
+    pub notification_type: NotificationType,
+    pub thread_id: Option<i64>,
+    pub actor_count: i32,
+    pub is_read: bool,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct NotificationActor {
+    pub notification_id: i64,
+    pub id: i64,
+    pub handle: Option<String>,
+    pub profile_img_url: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct NotificationPreference {
+    #[sqlx(rename = "type")]
+    pub notification_type: NotificationType,
+    pub enabled: bool,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct Session {
+    pub id: i64,
+    pub user_id: i64,
+
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+
+    pub is_revoked: bool,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct RefreshToken {
+    pub id: i64,
+    pub session_id: i64,
+    pub token_hash: String,
+
+    pub is_used: bool,
+    pub used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Device information recorded when a session is created.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
@@ -2,17 +2,80 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
-pub struct Thread {
+use crate::domain::dto::thread::{MentionEntity, ResponseThread};
+
+// What a restore needs to know about a soft-deleted thread.
+#[derive(Debug, Clone, FromRow)]
+pub struct DeletedThread {
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// `entry_count` threads are ranked so far, all of them once `is_complete`.
+#[derive(Debug, Clone, FromRow)]
+pub struct ThreadSnapshot {
     pub id: i64,
-    pub user_id: i64,
+    pub parent_thread: Option<i64>,
+    pub entry_count: i64,
+    pub is_complete: bool,
+}
+
+// A thread read from a snapshot, `position` is its rank when the snapshot was taken.
+#[derive(Debug, Clone, FromRow)]
+pub struct RankedThread {
+    #[sqlx(flatten)]
+    pub thread: ResponseThread,
+    pub position: i64,
+}
 
+// A reply read by the tree query, `depth` is 1 for the direct replies.
+#[derive(Debug, Clone, FromRow)]
+pub struct ThreadTreeRow {
+    #[sqlx(flatten)]
+    pub thread: ResponseThread,
+    pub depth: i32,
+}
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct ThreadRevision {
+    pub thread_id: i64,
+    pub revision: i32,
+    pub editor_id: Option<i64>,
     pub title: Option<String>,
     pub content: String,
-    pub parent_thread: i64,
-
-    pub is_deleted: bool,
-    pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+}
+
+// A search match with its relevance and highlighted text.
+#[derive(Debug, Clone, FromRow)]
+pub struct ThreadSearchRow {
+    #[sqlx(flatten)]
+    pub thread: ResponseThread,
+    pub rank: f32,
+    pub title_highlight: Option<String>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct TrendingTag {
+    pub name: String,
+    pub thread_count: i64,
+    pub score: f64,
+}
+
+// A mention resolved to its user, to be stored.
+#[derive(Debug, Clone)]
+pub struct ThreadMention {
+    pub user_id: i64,
+    pub start: i32,
+    pub end: i32,
+}
+
+// A stored mention with the thread it belongs to, read for a page of threads.
+#[derive(Debug, Clone, FromRow)]
+pub struct ThreadMentionRow {
+    pub thread_id: i64,
+    #[sqlx(flatten)]
+    pub mention: MentionEntity,
 }
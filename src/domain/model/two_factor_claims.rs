@@ -0,0 +1,54 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::errors::Error;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::{config, utils::jwt_keys};
+
+const CHALLENGE_PURPOSE: &str = "2fa_challenge";
+
+// Short-lived token returned by signin when the account has 2FA enabled.
+// It only proves that the password was correct and cannot be used as an access token.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TwoFactorChallengeClaims {
+    // issued at, expiration
+    iat: u64,
+    exp: u64,
+
+    // data
+    pub id: i64,
+    purpose: String,
+}
+
+impl TwoFactorChallengeClaims {
+    pub fn new(user_id: i64) -> Self {
+        let time_now = Utc::now();
+        let issued_at = time_now.timestamp() as u64;
+        let expiration = (time_now
+            + Duration::seconds(
+                config::env::envs().two_factor_challenge_expiration_in_seconds,
+            ))
+        .timestamp() as u64;
+
+        TwoFactorChallengeClaims {
+            iat: issued_at,
+            exp: expiration,
+            id: user_id,
+            purpose: CHALLENGE_PURPOSE.to_string(),
+        }
+    }
+
+    pub fn encode_jwt(claims: TwoFactorChallengeClaims) -> Result<String, Error> {
+        jwt_keys::key_ring().encode(&claims).map_err(|err| {
+            error!("Error encoding 2FA challenge: {}", err);
+            err
+        })
+    }
+
+    pub fn decode_jwt(token: &str) -> Option<TwoFactorChallengeClaims> {
+        jwt_keys::key_ring()
+            .decode::<TwoFactorChallengeClaims>(token)
+            .ok()
+            .filter(|claims| claims.purpose == CHALLENGE_PURPOSE)
+    }
+}
@@ -14,8 +14,54 @@ pub struct User {
     pub bio: Option<String>,
 
     pub is_profile_complete: bool,
+    pub is_email_verified: bool,
+    pub email_verified_at: Option<DateTime<Utc>>,
+
+    pub totp_secret: Option<String>,
+    pub is_totp_enabled: bool,
+    pub totp_last_used_step: Option<i64>,
+
+    pub role: UserRole,
+    pub is_suspended: bool,
+    pub suspended_at: Option<DateTime<Utc>>,
+
     pub is_deleted: bool,
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+// Declared from the least to the most privileged, so roles can be compared.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    sqlx::Type,
+    Deserialize,
+    Serialize,
+)]
+#[sqlx(type_name = "user_role_enum", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UserRole {
+    #[default]
+    #[serde(rename = "USER")]
+    User,
+    #[serde(rename = "MODERATOR")]
+    Moderator,
+    #[serde(rename = "ADMIN")]
+    Admin,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::User => "USER",
+            UserRole::Moderator => "MODERATOR",
+            UserRole::Admin => "ADMIN",
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
+pub struct UserToken {
+    pub id: i64,
+    pub user_id: i64,
+
+    pub purpose: TokenPurpose,
+    pub token_hash: String,
+
+    pub used_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, sqlx::Type, Deserialize, Serialize)]
+#[sqlx(type_name = "user_token_purpose_enum", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenPurpose {
+    #[serde(rename = "PASSWORD_RESET")]
+    PasswordReset,
+    #[serde(rename = "EMAIL_VERIFICATION")]
+    EmailVerification,
+}
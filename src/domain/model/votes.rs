@@ -2,13 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Clone, FromRow, Deserialize, Serialize)]
-pub struct Votes {
-    pub user_id: i64,
-    pub thread_id: i64,
-    pub reaction: ReactionType,
-    pub created_at: DateTime<Utc>,
-}
+use crate::domain::dto::thread::ResponseThread;
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, sqlx::Type, Deserialize, Serialize)]
 #[sqlx(type_name = "reaction_enum", rename_all = "UPPERCASE")]
@@ -18,3 +12,11 @@ pub enum ReactionType {
     #[serde(rename = "DOWN")]
     Down,
 }
+
+// A thread in the upvoted / downvoted lists, ordered by when the user reacted.
+#[derive(Debug, Clone, FromRow)]
+pub struct ReactedThread {
+    #[sqlx(flatten)]
+    pub thread: ResponseThread,
+    pub reacted_at: DateTime<Utc>,
+}
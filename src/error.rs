@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -15,6 +15,8 @@ pub enum CustomError {
     JWTError(String),
     AlreadyRegisteredUser(String),
     InvalidCredentials,
+    InvalidRefreshToken,
+    InvalidToken,
     NotFound,
     InternalError(String),
     PermissionDenied(String),
@@ -25,8 +27,32 @@ pub enum CustomError {
     TrySelfFollow,
     AlreadyFollowed,
     PasswordMismatch,
+    InvalidEmail,
+    EmailNotVerified,
+    EmailAlreadyVerified,
+    VerificationMailThrottled(i64),
+    InvalidTwoFactorCode,
+    TwoFactorAlreadyEnabled,
+    TwoFactorNotEnabled,
+    TooManyLoginAttempts(i64),
+    InsufficientScope(String),
+    InvalidAccessTokenRequest(String),
+    AccountSuspended,
+    InvalidCursor,
+    CursorExpired,
+    EditWindowExpired,
+    RestoreWindowExpired,
+    InvalidThreadUpdate(String),
+    InvalidSearchQuery(String),
+    InvalidTag,
+    InvalidWebhook(String),
+    InvalidBookmarkFolder(String),
+    VersionConflict,
+    PreconditionFailed,
     AlreadyReacted,
     NotReacted,
+    AlreadyBookmarked,
+    NotBookmarked,
 }
 
 impl CustomError {
@@ -40,10 +66,10 @@ impl IntoResponse for CustomError {
     fn into_response(self) -> Response {
         match self {
             CustomError::DatabaseError(ref message) => {
-                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, &message)
+                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, message)
             }
             CustomError::JWTError(ref message) => {
-                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, &message)
+                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, message)
             }
             CustomError::AlreadyRegisteredUser(ref user_email) => self.response_helper(
                 StatusCode::CONFLICT,
@@ -52,17 +78,24 @@ impl IntoResponse for CustomError {
             CustomError::InvalidCredentials => {
                 self.response_helper(StatusCode::BAD_REQUEST, "Invalid credentials")
             }
+            CustomError::InvalidRefreshToken => self.response_helper(
+                StatusCode::UNAUTHORIZED,
+                "Invalid or expired refresh token. Please sign in again.",
+            ),
+            CustomError::InvalidToken => {
+                self.response_helper(StatusCode::BAD_REQUEST, "Invalid or expired token")
+            }
             CustomError::NotFound => {
                 self.response_helper(StatusCode::NOT_FOUND, "Data not found")
             }
             CustomError::InternalError(ref message) => {
-                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, &message)
+                self.response_helper(StatusCode::INTERNAL_SERVER_ERROR, message)
             }
             CustomError::PermissionDenied(ref message) => {
-                self.response_helper(StatusCode::FORBIDDEN, &message)
+                self.response_helper(StatusCode::FORBIDDEN, message)
             }
             CustomError::Unauthorized(ref message) => {
-                self.response_helper(StatusCode::UNAUTHORIZED, &message)
+                self.response_helper(StatusCode::UNAUTHORIZED, message)
             }
             CustomError::ProfileNotCreated => self.response_helper(
                 StatusCode::NOT_FOUND,
@@ -86,6 +119,99 @@ impl IntoResponse for CustomError {
             CustomError::PasswordMismatch => {
                 self.response_helper(StatusCode::BAD_REQUEST, "Password not matched")
             }
+            CustomError::InvalidEmail => {
+                self.response_helper(StatusCode::BAD_REQUEST, "Invalid email address")
+            }
+            CustomError::EmailNotVerified => self.response_helper(
+                StatusCode::FORBIDDEN,
+                "Please verify your email address to continue.",
+            ),
+            CustomError::EmailAlreadyVerified => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "Your email address is already verified",
+            ),
+            CustomError::VerificationMailThrottled(retry_after) => self.response_helper(
+                StatusCode::TOO_MANY_REQUESTS,
+                &format!(
+                    "Verification mail was sent recently. Please try again in {} seconds.",
+                    retry_after
+                ),
+            ),
+            CustomError::InvalidTwoFactorCode => self.response_helper(
+                StatusCode::UNAUTHORIZED,
+                "Invalid two-factor authentication code",
+            ),
+            CustomError::TwoFactorAlreadyEnabled => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "Two-factor authentication is already enabled",
+            ),
+            CustomError::TwoFactorNotEnabled => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "Two-factor authentication is not enabled. Please enroll first.",
+            ),
+            CustomError::TooManyLoginAttempts(retry_after) => {
+                let mut response = self.response_helper(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    &format!(
+                        "Too many failed signin attempts. Please try again in {} seconds.",
+                        retry_after
+                    ),
+                );
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+                response
+            }
+            CustomError::InsufficientScope(ref scope) => self.response_helper(
+                StatusCode::FORBIDDEN,
+                &format!("This access token is missing the '{}' scope", scope),
+            ),
+            CustomError::InvalidAccessTokenRequest(ref message) => {
+                self.response_helper(StatusCode::BAD_REQUEST, message)
+            }
+            CustomError::AccountSuspended => self.response_helper(
+                StatusCode::FORBIDDEN,
+                "Your account has been suspended. Please contact an administrator.",
+            ),
+            CustomError::InvalidCursor => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "Invalid pagination cursor. Please start again from the first page.",
+            ),
+            CustomError::CursorExpired => self.response_helper(
+                StatusCode::GONE,
+                "This list has expired. Please start again from the first page.",
+            ),
+            CustomError::EditWindowExpired => self.response_helper(
+                StatusCode::FORBIDDEN,
+                "This thread can no longer be edited",
+            ),
+            CustomError::RestoreWindowExpired => self.response_helper(
+                StatusCode::FORBIDDEN,
+                "This thread can no longer be restored",
+            ),
+            CustomError::InvalidThreadUpdate(ref message) => {
+                self.response_helper(StatusCode::BAD_REQUEST, message)
+            }
+            CustomError::VersionConflict => self.response_helper(
+                StatusCode::CONFLICT,
+                "This thread was changed since it was read. Please reload it and try again.",
+            ),
+            CustomError::PreconditionFailed => self.response_helper(
+                StatusCode::PRECONDITION_FAILED,
+                "This thread does not match the If-Match version",
+            ),
+            CustomError::InvalidSearchQuery(ref message) => {
+                self.response_helper(StatusCode::BAD_REQUEST, message)
+            }
+            CustomError::InvalidTag => {
+                self.response_helper(StatusCode::BAD_REQUEST, "Invalid tag")
+            }
+            CustomError::InvalidWebhook(ref message) => {
+                self.response_helper(StatusCode::BAD_REQUEST, message)
+            }
+            CustomError::InvalidBookmarkFolder(ref message) => {
+                self.response_helper(StatusCode::BAD_REQUEST, message)
+            }
             CustomError::AlreadyReacted => self.response_helper(
                 StatusCode::BAD_REQUEST,
                 "You have already reacted that thread",
@@ -94,6 +220,14 @@ impl IntoResponse for CustomError {
                 StatusCode::BAD_REQUEST,
                 "You have not reacted this thread. Please check your reacting list.",
             ),
+            CustomError::AlreadyBookmarked => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "You have already bookmarked that thread",
+            ),
+            CustomError::NotBookmarked => self.response_helper(
+                StatusCode::BAD_REQUEST,
+                "You have not bookmarked this thread",
+            ),
         }
     }
 }
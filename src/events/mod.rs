@@ -0,0 +1,78 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::domain::model::notification::NotificationType;
+
+// Events a slow client can fall behind by before it misses some.
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+// Something that happened, pushed to the clients of `GET api/stream`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    // only for `user_id`, the client fetches the notification list
+    Notification {
+        user_id: i64,
+        notification_type: NotificationType,
+        thread_id: Option<i64>,
+    },
+    // for the clients viewing `parent_thread`
+    Reply {
+        parent_thread: i64,
+        thread_id: i64,
+        user_id: i64,
+    },
+    // for the clients viewing `thread_id`
+    Votes {
+        thread_id: i64,
+        votes: i64,
+    },
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Notification { .. } => "notification",
+            Event::Reply { .. } => "reply",
+            Event::Votes { .. } => "votes",
+        }
+    }
+
+    pub fn is_visible_to(&self, user_id: i64, threads: &HashSet<i64>) -> bool {
+        match self {
+            Event::Notification { user_id: recipient_id, .. } => *recipient_id == user_id,
+            Event::Reply { parent_thread, .. } => threads.contains(parent_thread),
+            Event::Votes { thread_id, .. } => threads.contains(thread_id),
+        }
+    }
+}
+
+// In-process fan-out from the services to the open streams. Events are not
+// persisted, a client that reconnects refetches what it shows.
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: Event) {
+        // fails only when nobody is listening
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
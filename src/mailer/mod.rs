@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::CustomError;
+
+pub mod outbox_mailer;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// Outgoing mail goes through this trait so the delivery backend (SMTP, a mail API,
+// or the local outbox) can be swapped in `api::server::di` without touching services.
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<(), CustomError>;
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::Serialize;
+use tracing::info;
+
+use super::{Mail, Mailer};
+use crate::{error::CustomError, utils::crypto};
+
+// Writes every mail as a JSON file into a local directory instead of delivering it.
+// Useful for development and tests where no mail server is available.
+pub struct OutboxMailer {
+    from: String,
+    outbox_dir: PathBuf,
+}
+
+#[derive(Serialize)]
+struct OutboxMail<'a> {
+    from: &'a str,
+    to: &'a str,
+    subject: &'a str,
+    body: &'a str,
+    created_at: String,
+}
+
+impl OutboxMailer {
+    pub fn new(from: &str, outbox_dir: &str) -> Self {
+        Self { from: from.to_string(), outbox_dir: PathBuf::from(outbox_dir) }
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    async fn send(&self, mail: Mail) -> Result<(), CustomError> {
+        let now = Utc::now();
+        let outbox_mail = OutboxMail {
+            from: &self.from,
+            to: &mail.to,
+            subject: &mail.subject,
+            body: &mail.body,
+            created_at: now.to_rfc3339(),
+        };
+        let content = serde_json::to_vec_pretty(&outbox_mail)
+            .map_err(|err| CustomError::InternalError(err.to_string()))?;
+
+        // file names sort by creation time, the suffix keeps them unique
+        let suffix = &crypto::generate_token()[..8];
+        let file_path = self.outbox_dir.join(format!(
+            "{}-{}.json",
+            now.format("%Y%m%d%H%M%S%3f"),
+            suffix
+        ));
+
+        tokio::fs::create_dir_all(&self.outbox_dir)
+            .await
+            .map_err(|err| CustomError::InternalError(err.to_string()))?;
+        tokio::fs::write(&file_path, content)
+            .await
+            .map_err(|err| CustomError::InternalError(err.to_string()))?;
+
+        info!("Mail to {} written to {:?}", mail.to, file_path);
+        Ok(())
+    }
+}
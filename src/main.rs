@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use dotenvy::dotenv;
 use sqlx::postgres::PgPoolOptions;
 use tracing::info;
@@ -6,14 +8,23 @@ mod api;
 mod config;
 mod domain;
 mod error;
+mod events;
+mod mailer;
 mod repository;
 mod services;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
     tracing_subscriber::fmt::init();
+    // fail fast on an invalid key ring, password hashing configuration or default
+    // secrets outside development
+    utils::jwt_keys::key_ring();
+    utils::cursor::cursor_secret();
+    utils::password_hash::configured_scheme();
 
     let db_pool = PgPoolOptions::new()
         .max_connections(5)
@@ -24,5 +35,7 @@ async fn main() {
     let listener = tokio::net::TcpListener::bind(&"0.0.0.0:8080").await.unwrap();
 
     info!("LISTENING on {:?}\n", listener.local_addr());
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
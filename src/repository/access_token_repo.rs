@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::domain::model::access_token::PersonalAccessToken;
+
+#[async_trait]
+pub trait AccessTokenRepositoryTrait: Send + Sync {
+    async fn create_token(
+        &self,
+        user_id: i64,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<PersonalAccessToken>;
+    async fn find_active_token(
+        &self,
+        token_hash: &str,
+    ) -> RepositoryResult<PersonalAccessToken>;
+    async fn list_active_token(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<PersonalAccessToken>>;
+    async fn touch_token(&self, id: i64) -> RepositoryResult<()>;
+    async fn revoke_token(&self, id: i64, user_id: i64) -> RepositoryResult<bool>;
+}
+
+pub struct AccessTokenRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl AccessTokenRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl AccessTokenRepositoryTrait for AccessTokenRepository {
+    async fn create_token(
+        &self,
+        user_id: i64,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: Vec<String>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<PersonalAccessToken> {
+        let access_token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            INSERT INTO personal_access_tokens (user_id, name, token_hash, token_prefix, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(scopes)
+        .bind(expires_at)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(access_token)
+    }
+
+    async fn find_active_token(
+        &self,
+        token_hash: &str,
+    ) -> RepositoryResult<PersonalAccessToken> {
+        let access_token = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT * FROM personal_access_tokens
+            WHERE token_hash = $1
+            AND revoked_at IS NULL
+            AND (expires_at IS NULL OR expires_at > NOW())
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(access_token)
+    }
+
+    async fn list_active_token(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<PersonalAccessToken>> {
+        let access_token_list = sqlx::query_as::<_, PersonalAccessToken>(
+            r#"
+            SELECT * FROM personal_access_tokens
+            WHERE user_id = $1
+            AND revoked_at IS NULL
+            AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(access_token_list)
+    }
+
+    async fn touch_token(&self, id: i64) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            "UPDATE personal_access_tokens SET last_used_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn revoke_token(&self, id: i64, user_id: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE personal_access_tokens SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+}
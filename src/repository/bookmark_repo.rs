@@ -0,0 +1,273 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::domain::model::{
+    bookmark::{BookmarkFolder, BookmarkedThread},
+    cursor_claims::CursorClaims,
+};
+
+#[async_trait]
+pub trait BookmarkRepositoryTrait: Send + Sync {
+    async fn add_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> RepositoryResult<bool>;
+    async fn remove_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+    ) -> RepositoryResult<bool>;
+    async fn move_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> RepositoryResult<bool>;
+    async fn list_bookmarked(
+        &self,
+        user_id: i64,
+        thread_ids: &[i64],
+    ) -> RepositoryResult<Vec<i64>>;
+    async fn list_bookmarked_thread(
+        &self,
+        user_id: i64,
+        folder_id: Option<i64>,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<BookmarkedThread>>;
+    async fn create_folder(
+        &self,
+        user_id: i64,
+        name: &str,
+    ) -> RepositoryResult<BookmarkFolder>;
+    async fn list_folder(&self, user_id: i64) -> RepositoryResult<Vec<BookmarkFolder>>;
+    async fn find_folder(
+        &self,
+        id: i64,
+        user_id: i64,
+    ) -> RepositoryResult<BookmarkFolder>;
+    async fn rename_folder(
+        &self,
+        id: i64,
+        user_id: i64,
+        name: &str,
+    ) -> RepositoryResult<BookmarkFolder>;
+    async fn delete_folder(&self, id: i64, user_id: i64) -> RepositoryResult<bool>;
+}
+
+pub struct BookmarkRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl BookmarkRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl BookmarkRepositoryTrait for BookmarkRepository {
+    async fn add_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "INSERT INTO bookmarks (user_id, thread_id, folder_id) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(thread_id)
+        .bind(folder_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+        Ok(affected_rows > 0)
+    }
+
+    async fn remove_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+    ) -> RepositoryResult<bool> {
+        let affected_rows =
+            sqlx::query("DELETE FROM bookmarks WHERE user_id = $1 AND thread_id = $2")
+                .bind(user_id)
+                .bind(thread_id)
+                .execute(&*self.conn)
+                .await?
+                .rows_affected();
+        Ok(affected_rows > 0)
+    }
+
+    async fn move_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE bookmarks SET folder_id = $3 WHERE user_id = $1 AND thread_id = $2",
+        )
+        .bind(user_id)
+        .bind(thread_id)
+        .bind(folder_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+        Ok(affected_rows > 0)
+    }
+
+    // The ones of `thread_ids` the user saved, for a whole page in one query.
+    async fn list_bookmarked(
+        &self,
+        user_id: i64,
+        thread_ids: &[i64],
+    ) -> RepositoryResult<Vec<i64>> {
+        let bookmarked = sqlx::query_scalar::<_, i64>(
+            "SELECT thread_id FROM bookmarks WHERE user_id = $1 AND thread_id = ANY($2)",
+        )
+        .bind(user_id)
+        .bind(thread_ids)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(bookmarked)
+    }
+
+    // The latest saved first, all folders when `folder_id` is `None`.
+    async fn list_bookmarked_thread(
+        &self,
+        user_id: i64,
+        folder_id: Option<i64>,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<BookmarkedThread>> {
+        let bookmarked_list = sqlx::query_as::<_, BookmarkedThread>(
+            r#"
+            SELECT
+                t.*,
+                (COALESCE(SUM(CASE WHEN vts.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                COALESCE(SUM(CASE WHEN vts.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
+                COALESCE(MAX(v.view_count), 0) AS views,
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count,
+                b.created_at AS bookmarked_at
+            FROM thread t
+            JOIN bookmarks b
+                ON b.thread_id = t.id
+                AND b.user_id = $1
+            LEFT JOIN votes vts
+                ON vts.thread_id = t.id
+            LEFT JOIN views v
+                ON v.thread_id = t.id
+            WHERE t.is_deleted = FALSE
+            AND ($2::BIGINT IS NULL OR b.folder_id = $2)
+            AND ($3::TIMESTAMPTZ IS NULL OR (b.created_at, t.id) < ($3, $4))
+            GROUP BY t.id, b.created_at
+            ORDER BY bookmarked_at DESC, t.id DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(user_id)
+        .bind(folder_id)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(bookmarked_list)
+    }
+
+    async fn create_folder(
+        &self,
+        user_id: i64,
+        name: &str,
+    ) -> RepositoryResult<BookmarkFolder> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(
+            r#"
+            INSERT INTO bookmark_folders (user_id, name) VALUES ($1, $2)
+            RETURNING id, name, 0::BIGINT AS bookmark_count, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(folder)
+    }
+
+    async fn list_folder(&self, user_id: i64) -> RepositoryResult<Vec<BookmarkFolder>> {
+        let folders = sqlx::query_as::<_, BookmarkFolder>(
+            r#"
+            SELECT
+                f.id, f.name, f.created_at, f.updated_at,
+                (SELECT COUNT(*) FROM bookmarks b WHERE b.folder_id = f.id) AS bookmark_count
+            FROM bookmark_folders f
+            WHERE f.user_id = $1
+            ORDER BY LOWER(f.name), f.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(folders)
+    }
+
+    async fn find_folder(
+        &self,
+        id: i64,
+        user_id: i64,
+    ) -> RepositoryResult<BookmarkFolder> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(
+            r#"
+            SELECT
+                f.id, f.name, f.created_at, f.updated_at,
+                (SELECT COUNT(*) FROM bookmarks b WHERE b.folder_id = f.id) AS bookmark_count
+            FROM bookmark_folders f
+            WHERE f.id = $1 AND f.user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(folder)
+    }
+
+    async fn rename_folder(
+        &self,
+        id: i64,
+        user_id: i64,
+        name: &str,
+    ) -> RepositoryResult<BookmarkFolder> {
+        let folder = sqlx::query_as::<_, BookmarkFolder>(
+            r#"
+            UPDATE bookmark_folders f SET name = $3, updated_at = NOW()
+            WHERE f.id = $1 AND f.user_id = $2
+            RETURNING
+                f.id, f.name, f.created_at, f.updated_at,
+                (SELECT COUNT(*) FROM bookmarks b WHERE b.folder_id = f.id) AS bookmark_count
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(folder)
+    }
+
+    async fn delete_folder(&self, id: i64, user_id: i64) -> RepositoryResult<bool> {
+        let affected_rows =
+            sqlx::query("DELETE FROM bookmark_folders WHERE id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(&*self.conn)
+                .await?
+                .rows_affected();
+        Ok(affected_rows > 0)
+    }
+}
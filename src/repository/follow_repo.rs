@@ -131,13 +131,14 @@ impl FollowRepositoryTrait for FollowRepository {
             FROM follow f
             JOIN users u ON f.user_id = u.id
             WHERE f.follower_id = $1
-            AND f.created_at < $2
-            ORDER BY f.created_at DESC
-            LIMIT $3
+            AND ($2::TIMESTAMPTZ IS NULL OR (f.created_at, u.id) < ($2, $3))
+            ORDER BY f.created_at DESC, u.id DESC
+            LIMIT $4
             "#,
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
@@ -159,13 +160,14 @@ impl FollowRepositoryTrait for FollowRepository {
             FROM follow f
             JOIN users u ON f.follower_id = u.id
             WHERE f.user_id = $1
-            AND f.created_at < $2
-            ORDER BY f.created_at DESC
-            LIMIT $3
+            AND ($2::TIMESTAMPTZ IS NULL OR (f.created_at, u.id) < ($2, $3))
+            ORDER BY f.created_at DESC, u.id DESC
+            LIMIT $4
             "#,
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
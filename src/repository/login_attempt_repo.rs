@@ -0,0 +1,50 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::domain::model::{login_attempt::LoginFailureReason, session::ClientInfo};
+
+#[async_trait]
+pub trait LoginAttemptRepositoryTrait: Send + Sync {
+    async fn record_failed_attempt(
+        &self,
+        user_id: Option<i64>,
+        email: &str,
+        reason: LoginFailureReason,
+        client_info: &ClientInfo,
+    ) -> RepositoryResult<()>;
+}
+
+pub struct LoginAttemptRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl LoginAttemptRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl LoginAttemptRepositoryTrait for LoginAttemptRepository {
+    async fn record_failed_attempt(
+        &self,
+        user_id: Option<i64>,
+        email: &str,
+        reason: LoginFailureReason,
+        client_info: &ClientInfo,
+    ) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            "INSERT INTO login_attempts (user_id, email, reason, user_agent, ip_address) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(user_id)
+        .bind(email)
+        .bind(reason)
+        .bind(&client_info.user_agent)
+        .bind(&client_info.ip_address)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+use super::RepositoryResult;
+use crate::{domain::model::login_attempt::LoginThrottle, error::CustomError};
+
+// Entries are pruned once the in-memory store grows past this size.
+const IN_MEMORY_PRUNE_THRESHOLD: usize = 10_000;
+
+#[async_trait]
+pub trait LoginThrottleRepositoryTrait: Send + Sync {
+    async fn find_throttle(
+        &self,
+        throttle_key: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>>;
+    async fn register_failure(
+        &self,
+        throttle_key: &str,
+        window_start: DateTime<Utc>,
+    ) -> RepositoryResult<i32>;
+    async fn lock_until(
+        &self,
+        throttle_key: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+    async fn clear_throttle(&self, throttle_key: &str) -> RepositoryResult<()>;
+}
+
+pub struct LoginThrottleRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl LoginThrottleRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl LoginThrottleRepositoryTrait for LoginThrottleRepository {
+    async fn find_throttle(
+        &self,
+        throttle_key: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        let throttle = sqlx::query_as::<_, LoginThrottle>(
+            "SELECT * FROM login_throttles WHERE throttle_key = $1",
+        )
+        .bind(throttle_key)
+        .fetch_optional(&*self.conn)
+        .await?;
+        Ok(throttle)
+    }
+
+    // Failures older than `window_start` are forgotten and the count starts over.
+    async fn register_failure(
+        &self,
+        throttle_key: &str,
+        window_start: DateTime<Utc>,
+    ) -> RepositoryResult<i32> {
+        let failure_count = sqlx::query_scalar::<_, i32>(
+            r#"
+            INSERT INTO login_throttles (throttle_key, failure_count) VALUES ($1, 1)
+            ON CONFLICT (throttle_key) DO UPDATE SET
+                failure_count = CASE
+                    WHEN login_throttles.last_failed_at < $2 THEN 1
+                    ELSE login_throttles.failure_count + 1
+                END,
+                last_failed_at = NOW()
+            RETURNING failure_count
+            "#,
+        )
+        .bind(throttle_key)
+        .bind(window_start)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(failure_count)
+    }
+
+    async fn lock_until(
+        &self,
+        throttle_key: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            "UPDATE login_throttles SET locked_until = $1 WHERE throttle_key = $2",
+        )
+        .bind(locked_until)
+        .bind(throttle_key)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn clear_throttle(&self, throttle_key: &str) -> RepositoryResult<()> {
+        let _ = sqlx::query("DELETE FROM login_throttles WHERE throttle_key = $1")
+            .bind(throttle_key)
+            .execute(&*self.conn)
+            .await?;
+        Ok(())
+    }
+}
+
+// Keeps the counters in the process memory. Counters are lost on restart and
+// are not shared between instances, so only use it for a single instance.
+#[derive(Default)]
+pub struct InMemoryLoginThrottleRepository {
+    throttles: Mutex<HashMap<String, LoginThrottle>>,
+}
+
+impl InMemoryLoginThrottleRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_throttles(
+        &self,
+    ) -> RepositoryResult<std::sync::MutexGuard<'_, HashMap<String, LoginThrottle>>> {
+        self.throttles.lock().map_err(|_| {
+            CustomError::InternalError("Login throttle store is poisoned".to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl LoginThrottleRepositoryTrait for InMemoryLoginThrottleRepository {
+    async fn find_throttle(
+        &self,
+        throttle_key: &str,
+    ) -> RepositoryResult<Option<LoginThrottle>> {
+        Ok(self.lock_throttles()?.get(throttle_key).cloned())
+    }
+
+    async fn register_failure(
+        &self,
+        throttle_key: &str,
+        window_start: DateTime<Utc>,
+    ) -> RepositoryResult<i32> {
+        let mut throttles = self.lock_throttles()?;
+        let now = Utc::now();
+        if throttles.len() > IN_MEMORY_PRUNE_THRESHOLD {
+            throttles.retain(|_, throttle| {
+                throttle.last_failed_at >= window_start
+                    || throttle.locked_until.is_some_and(|until| until > now)
+            });
+        }
+
+        let throttle =
+            throttles.entry(throttle_key.to_string()).or_insert_with(|| LoginThrottle {
+                throttle_key: throttle_key.to_string(),
+                failure_count: 0,
+                last_failed_at: now,
+                locked_until: None,
+            });
+        if throttle.last_failed_at < window_start {
+            throttle.failure_count = 0;
+        }
+        throttle.failure_count += 1;
+        throttle.last_failed_at = now;
+        Ok(throttle.failure_count)
+    }
+
+    async fn lock_until(
+        &self,
+        throttle_key: &str,
+        locked_until: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        if let Some(throttle) = self.lock_throttles()?.get_mut(throttle_key) {
+            throttle.locked_until = Some(locked_until);
+        }
+        Ok(())
+    }
+
+    async fn clear_throttle(&self, throttle_key: &str) -> RepositoryResult<()> {
+        self.lock_throttles()?.remove(throttle_key);
+        Ok(())
+    }
+}
@@ -1,9 +1,18 @@
 use crate::error::CustomError;
 
+pub mod access_token_repo;
+pub mod bookmark_repo;
 pub mod follow_repo;
+pub mod login_attempt_repo;
+pub mod login_throttle_repo;
+pub mod notification_repo;
+pub mod session_repo;
 pub mod thread_repo;
+pub mod two_factor_repo;
 pub mod user_repo;
+pub mod user_token_repo;
 pub mod views_repo;
 pub mod votes_repo;
+pub mod webhook_repo;
 
 pub type RepositoryResult<T> = Result<T, CustomError>;
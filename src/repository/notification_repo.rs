@@ -0,0 +1,262 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::{
+    domain::model::{
+        cursor_claims::CursorClaims,
+        notification::{
+            Notification, NotificationActor, NotificationPreference, NotificationType,
+        },
+    },
+    error::CustomError,
+};
+
+#[async_trait]
+pub trait NotificationRepositoryTrait: Send + Sync {
+    async fn add_notification(
+        &self,
+        user_id: i64,
+        notification_type: NotificationType,
+        thread_id: Option<i64>,
+        actor_id: i64,
+    ) -> RepositoryResult<()>;
+    async fn list_notification(
+        &self,
+        user_id: i64,
+        unread_only: bool,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<Notification>>;
+    async fn list_notification_actor(
+        &self,
+        notification_ids: &[i64],
+        per_notification: i64,
+    ) -> RepositoryResult<Vec<NotificationActor>>;
+    async fn count_unread_notification(&self, user_id: i64) -> RepositoryResult<i64>;
+    async fn mark_read(&self, user_id: i64, id: i64) -> RepositoryResult<()>;
+    async fn mark_all_read(&self, user_id: i64) -> RepositoryResult<u64>;
+    async fn list_preference(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<NotificationPreference>>;
+    async fn upsert_preference(
+        &self,
+        user_id: i64,
+        notification_type: NotificationType,
+        enabled: bool,
+    ) -> RepositoryResult<()>;
+}
+
+pub struct NotificationRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl NotificationRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl NotificationRepositoryTrait for NotificationRepository {
+    // Joins the unread group of the same type and thread, or starts a new one. An
+    // actor already in the group does not count twice.
+    async fn add_notification(
+        &self,
+        user_id: i64,
+        notification_type: NotificationType,
+        thread_id: Option<i64>,
+        actor_id: i64,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.conn.begin().await?;
+
+        let notification_id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO notifications (user_id, type, thread_id) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, type, COALESCE(thread_id, 0)) WHERE is_read = FALSE
+            DO UPDATE SET user_id = EXCLUDED.user_id
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(notification_type)
+        .bind(thread_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let inserted = sqlx::query(
+            "INSERT INTO notification_actors (notification_id, actor_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(notification_id)
+        .bind(actor_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if inserted > 0 {
+            let _ = sqlx::query(
+                r#"
+                UPDATE notifications
+                SET actor_count = actor_count + 1, last_actor_id = $1, updated_at = NOW()
+                WHERE id = $2
+                "#,
+            )
+            .bind(actor_id)
+            .bind(notification_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Newest activity first. Groups about a deleted thread are left out.
+    async fn list_notification(
+        &self,
+        user_id: i64,
+        unread_only: bool,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<Notification>> {
+        let notifications = sqlx::query_as::<_, Notification>(
+            r#"
+            SELECT n.*
+            FROM notifications n
+            WHERE n.user_id = $1
+            AND n.actor_count > 0
+            AND ($2 = FALSE OR n.is_read = FALSE)
+            AND (n.thread_id IS NULL OR EXISTS (
+                SELECT 1 FROM thread t WHERE t.id = n.thread_id AND t.is_deleted = FALSE
+            ))
+            AND ($3::TIMESTAMPTZ IS NULL OR (n.updated_at, n.id) < ($3, $4))
+            ORDER BY n.updated_at DESC, n.id DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(notifications)
+    }
+
+    // The latest `per_notification` actors of each group, deleted users left out.
+    async fn list_notification_actor(
+        &self,
+        notification_ids: &[i64],
+        per_notification: i64,
+    ) -> RepositoryResult<Vec<NotificationActor>> {
+        let actors = sqlx::query_as::<_, NotificationActor>(
+            r#"
+            SELECT notification_id, id, handle, profile_img_url
+            FROM (
+                SELECT
+                    a.notification_id, u.id, u.handle, u.profile_img_url,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY a.notification_id ORDER BY a.created_at DESC, u.id DESC
+                    ) AS actor_rank
+                FROM notification_actors a
+                JOIN users u ON u.id = a.actor_id
+                WHERE a.notification_id = ANY($1)
+                AND u.is_deleted = FALSE
+            ) ranked
+            WHERE actor_rank <= $2
+            ORDER BY notification_id, actor_rank
+            "#,
+        )
+        .bind(notification_ids)
+        .bind(per_notification)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(actors)
+    }
+
+    async fn count_unread_notification(&self, user_id: i64) -> RepositoryResult<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM notifications n
+            WHERE n.user_id = $1
+            AND n.actor_count > 0
+            AND n.is_read = FALSE
+            AND (n.thread_id IS NULL OR EXISTS (
+                SELECT 1 FROM thread t WHERE t.id = n.thread_id AND t.is_deleted = FALSE
+            ))
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(count)
+    }
+
+    async fn mark_read(&self, user_id: i64, id: i64) -> RepositoryResult<()> {
+        let affected_rows = sqlx::query(
+            r#"
+            UPDATE notifications SET is_read = TRUE, read_at = COALESCE(read_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        if affected_rows > 0 {
+            Ok(())
+        } else {
+            Err(CustomError::NotFound)
+        }
+    }
+
+    async fn mark_all_read(&self, user_id: i64) -> RepositoryResult<u64> {
+        let affected_rows = sqlx::query(
+            "UPDATE notifications SET is_read = TRUE, read_at = NOW() WHERE user_id = $1 AND is_read = FALSE",
+        )
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+        Ok(affected_rows)
+    }
+
+    async fn list_preference(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<NotificationPreference>> {
+        let preferences = sqlx::query_as::<_, NotificationPreference>(
+            "SELECT type, enabled FROM notification_preferences WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(preferences)
+    }
+
+    async fn upsert_preference(
+        &self,
+        user_id: i64,
+        notification_type: NotificationType,
+        enabled: bool,
+    ) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            r#"
+            INSERT INTO notification_preferences (user_id, type, enabled) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, type) DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(notification_type)
+        .bind(enabled)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::{
+    domain::model::session::{ClientInfo, RefreshToken, Session},
+    error::CustomError,
+};
+
+#[async_trait]
+pub trait SessionRepositoryTrait: Send + Sync {
+    async fn create_session(
+        &self,
+        user_id: i64,
+        refresh_token_hash: &str,
+        expires_at: DateTime<Utc>,
+        client_info: ClientInfo,
+    ) -> RepositoryResult<i64>;
+    async fn find_session_by_id(&self, id: i64) -> RepositoryResult<Session>;
+    async fn list_active_session(&self, user_id: i64) -> RepositoryResult<Vec<Session>>;
+    async fn touch_session(&self, id: i64) -> RepositoryResult<()>;
+    async fn revoke_session(&self, id: i64) -> RepositoryResult<bool>;
+    async fn revoke_user_session(&self, id: i64, user_id: i64) -> RepositoryResult<bool>;
+    async fn revoke_all_user_session(
+        &self,
+        user_id: i64,
+        except_session_id: Option<i64>,
+    ) -> RepositoryResult<u64>;
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> RepositoryResult<RefreshToken>;
+    async fn rotate_refresh_token(
+        &self,
+        used_token_id: i64,
+        session_id: i64,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<bool>;
+}
+
+pub struct SessionRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl SessionRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl SessionRepositoryTrait for SessionRepository {
+    async fn create_session(
+        &self,
+        user_id: i64,
+        refresh_token_hash: &str,
+        expires_at: DateTime<Utc>,
+        client_info: ClientInfo,
+    ) -> RepositoryResult<i64> {
+        let mut tx = self.conn.begin().await?;
+
+        let session_id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO sessions (user_id, expires_at, user_agent, ip_address) VALUES ($1, $2, $3, $4) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(expires_at)
+        .bind(client_info.user_agent)
+        .bind(client_info.ip_address)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query(
+            "INSERT INTO refresh_tokens (session_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+        )
+        .bind(session_id)
+        .bind(refresh_token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(session_id)
+    }
+
+    async fn find_session_by_id(&self, id: i64) -> RepositoryResult<Session> {
+        let session =
+            sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE id = $1")
+                .bind(id)
+                .fetch_one(&*self.conn)
+                .await?;
+        Ok(session)
+    }
+
+    async fn list_active_session(&self, user_id: i64) -> RepositoryResult<Vec<Session>> {
+        let session_list = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT * FROM sessions
+            WHERE user_id = $1
+            AND is_revoked = FALSE
+            AND expires_at > NOW()
+            ORDER BY last_used_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(session_list)
+    }
+
+    async fn touch_session(&self, id: i64) -> RepositoryResult<()> {
+        let _ = sqlx::query("UPDATE sessions SET last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&*self.conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn revoke_session(&self, id: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE sessions SET is_revoked = TRUE, revoked_at = NOW() WHERE id = $1 AND is_revoked = FALSE",
+        )
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+
+    async fn revoke_user_session(&self, id: i64, user_id: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE sessions SET is_revoked = TRUE, revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND is_revoked = FALSE",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+
+    async fn revoke_all_user_session(
+        &self,
+        user_id: i64,
+        except_session_id: Option<i64>,
+    ) -> RepositoryResult<u64> {
+        let affected_rows = sqlx::query(
+            r#"
+            UPDATE sessions SET is_revoked = TRUE, revoked_at = NOW()
+            WHERE user_id = $1
+            AND is_revoked = FALSE
+            AND ($2::BIGINT IS NULL OR id <> $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(except_session_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows)
+    }
+
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> RepositoryResult<RefreshToken> {
+        let refresh_token = sqlx::query_as::<_, RefreshToken>(
+            "SELECT * FROM refresh_tokens WHERE token_hash = $1",
+        )
+        .bind(token_hash)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(refresh_token)
+    }
+
+    // Marks the presented token as used and issues its successor in one transaction.
+    // Returns `false` when the token was already consumed by a concurrent request.
+    async fn rotate_refresh_token(
+        &self,
+        used_token_id: i64,
+        session_id: i64,
+        new_token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<bool> {
+        let mut tx = self.conn.begin().await?;
+
+        let affected_rows = sqlx::query(
+            "UPDATE refresh_tokens SET is_used = TRUE, used_at = NOW() WHERE id = $1 AND is_used = FALSE",
+        )
+        .bind(used_token_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if affected_rows == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        let _ = sqlx::query(
+            "INSERT INTO refresh_tokens (session_id, token_hash, expires_at) VALUES ($1, $2, $3)",
+        )
+        .bind(session_id)
+        .bind(new_token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        let affected_rows = sqlx::query(
+            "UPDATE sessions SET last_used_at = NOW(), expires_at = $1 WHERE id = $2",
+        )
+        .bind(expires_at)
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if affected_rows == 0 {
+            tx.rollback().await?;
+            return Err(CustomError::NotFound);
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
@@ -1,14 +1,26 @@
 use async_trait::async_trait;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
 use std::sync::Arc;
 
 use super::RepositoryResult;
 use crate::{
     domain::{
-        dto::thread::{RequestCreateThread, RequestUpdateThread, ResponseThread},
-        model::cursor_claims::CursorClaims,
+        dto::{
+            search::{ThreadSearchFilter, ThreadSearchType},
+            thread::{RequestCreateThread, RequestUpdateThread, ResponseThread},
+        },
+        model::{
+            cursor_claims::CursorClaims,
+            thread::{
+                DeletedThread, RankedThread, ThreadMention, ThreadMentionRow,
+                ThreadRevision, ThreadSearchRow, ThreadSnapshot, ThreadTreeRow,
+                TrendingTag,
+            },
+        },
     },
     error::CustomError,
+    utils,
 };
 
 #[async_trait]
@@ -17,8 +29,12 @@ pub trait ThreadRepositoryTrait: Send + Sync {
         &self,
         user_id: i64,
         new_thread: RequestCreateThread,
+        mentions: &[ThreadMention],
     ) -> RepositoryResult<i64>;
     async fn get_thread_by_id(&self, id: i64) -> RepositoryResult<ResponseThread>;
+    async fn get_visible_thread_by_id(&self, id: i64)
+        -> RepositoryResult<ResponseThread>;
+    async fn find_deleted_thread(&self, id: i64) -> RepositoryResult<DeletedThread>;
     async fn list_thread_by_user_id(
         &self,
         user_id: i64,
@@ -28,28 +44,95 @@ pub trait ThreadRepositoryTrait: Send + Sync {
     async fn update_thread(
         &self,
         id: i64,
+        editor_id: i64,
+        version: i32,
         new_thread: RequestUpdateThread,
-    ) -> RepositoryResult<ResponseThread>;
-    async fn delete_thread(&self, id: i64) -> RepositoryResult<bool>;
+        mentions: &[ThreadMention],
+    ) -> RepositoryResult<Option<ResponseThread>>;
+    async fn list_revision(
+        &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ThreadRevision>>;
+    async fn delete_thread(&self, id: i64, deleted_by: i64) -> RepositoryResult<bool>;
+    async fn restore_thread(&self, id: i64) -> RepositoryResult<bool>;
+    async fn purge_deleted_thread(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> RepositoryResult<u64>;
     async fn list_thread_by_following(
         &self,
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> RepositoryResult<Vec<ResponseThread>>;
-    async fn list_thread_by_popularity_score(
+    async fn list_thread_by_latest_created(
         &self,
         cursor: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> RepositoryResult<Vec<ResponseThread>>;
-    async fn list_thread_by_latest_created(
+    async fn list_reply_tree(
+        &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        max_depth: i32,
+        per_level: i64,
+    ) -> RepositoryResult<Vec<ThreadTreeRow>>;
+    async fn list_ancestor(
         &self,
+        thread_id: i64,
+    ) -> RepositoryResult<Vec<ResponseThread>>;
+    async fn find_or_create_snapshot(
+        &self,
+        parent_thread: Option<i64>,
+        bucket_at: DateTime<Utc>,
+        size: i64,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<ThreadSnapshot>;
+    async fn extend_snapshot(
+        &self,
+        snapshot_id: i64,
+        size: i64,
+    ) -> RepositoryResult<ThreadSnapshot>;
+    async fn find_snapshot(&self, id: i64) -> RepositoryResult<ThreadSnapshot>;
+    async fn list_snapshot_thread_id(
+        &self,
+        snapshot_id: i64,
+        max_position: i64,
+    ) -> RepositoryResult<Vec<i64>>;
+    async fn search_thread(
+        &self,
+        filter: ThreadSearchFilter,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ThreadSearchRow>>;
+    async fn list_thread_by_snapshot(
+        &self,
+        snapshot_id: i64,
+        after_position: i64,
+        limit: i64,
+    ) -> RepositoryResult<Vec<RankedThread>>;
+    async fn list_thread_by_tag(
+        &self,
+        tag: &str,
         cursor: CursorClaims,
         limit: i64,
     ) -> RepositoryResult<Vec<ResponseThread>>;
-    async fn list_subthread_by_parent_id(
+    async fn list_trending_tag(
         &self,
-        thread_id: i64,
+        created_after: DateTime<Utc>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<TrendingTag>>;
+    async fn list_mention(
+        &self,
+        thread_ids: &[i64],
+    ) -> RepositoryResult<Vec<ThreadMentionRow>>;
+    async fn list_thread_by_mentioned_user(
+        &self,
+        user_id: i64,
         cursor: CursorClaims,
         limit: i64,
     ) -> RepositoryResult<Vec<ResponseThread>>;
@@ -65,23 +148,112 @@ impl ThreadRepository {
     }
 }
 
+// Replaces the tags of a thread with the ones in `content`.
+async fn save_thread_tags(
+    conn: &mut PgConnection,
+    thread_id: i64,
+    content: &str,
+) -> RepositoryResult<()> {
+    let tags = utils::text::parse_tags(content);
+
+    let _ = sqlx::query(
+        "INSERT INTO tags (name) SELECT UNNEST($1::TEXT[]) ON CONFLICT (name) DO NOTHING",
+    )
+    .bind(&tags)
+    .execute(&mut *conn)
+    .await?;
+
+    let _ = sqlx::query(
+        r#"
+        DELETE FROM thread_tags
+        WHERE thread_id = $1
+        AND tag_id NOT IN (SELECT id FROM tags WHERE name = ANY($2))
+        "#,
+    )
+    .bind(thread_id)
+    .bind(&tags)
+    .execute(&mut *conn)
+    .await?;
+
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO thread_tags (thread_id, tag_id)
+        SELECT $1, id FROM tags WHERE name = ANY($2)
+        ON CONFLICT (thread_id, tag_id) DO NOTHING
+        "#,
+    )
+    .bind(thread_id)
+    .bind(&tags)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+// Replaces the mentions of a thread, resolved by the service from its content.
+async fn save_thread_mentions(
+    conn: &mut PgConnection,
+    thread_id: i64,
+    mentions: &[ThreadMention],
+) -> RepositoryResult<()> {
+    let _ = sqlx::query("DELETE FROM thread_mentions WHERE thread_id = $1")
+        .bind(thread_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let user_ids: Vec<i64> = mentions.iter().map(|mention| mention.user_id).collect();
+    let starts: Vec<i32> = mentions.iter().map(|mention| mention.start).collect();
+    let ends: Vec<i32> = mentions.iter().map(|mention| mention.end).collect();
+    let _ = sqlx::query(
+        r#"
+        INSERT INTO thread_mentions (thread_id, user_id, start_offset, end_offset)
+        SELECT $1, * FROM UNNEST($2::BIGINT[], $3::INTEGER[], $4::INTEGER[])
+        "#,
+    )
+    .bind(thread_id)
+    .bind(user_ids)
+    .bind(starts)
+    .bind(ends)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
 #[async_trait]
 impl ThreadRepositoryTrait for ThreadRepository {
     async fn create_thread(
         &self,
         user_id: i64,
         new_thread: RequestCreateThread,
+        mentions: &[ThreadMention],
     ) -> RepositoryResult<i64> {
+        let mut tx = self.conn.begin().await?;
+
         let thread_id = sqlx::query_scalar::<_, i64>(
             "INSERT INTO thread (user_id, title, content, parent_thread) VALUES ($1, $2, $3, $4) RETURNING id",
         )
         .bind(user_id)
-        .bind(new_thread.title)
+        .bind(&new_thread.title)
         .bind(&new_thread.content)
         .bind(new_thread.parent_thread)
-        .fetch_one(&*self.conn)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query(
+            "INSERT INTO thread_revisions (thread_id, revision, editor_id, title, content) VALUES ($1, 1, $2, $3, $4)",
+        )
+        .bind(thread_id)
+        .bind(user_id)
+        .bind(&new_thread.title)
+        .bind(&new_thread.content)
+        .execute(&mut *tx)
         .await?;
 
+        save_thread_tags(&mut tx, thread_id, &new_thread.content).await?;
+        save_thread_mentions(&mut tx, thread_id, mentions).await?;
+
+        tx.commit().await?;
         Ok(thread_id)
     }
 
@@ -109,6 +281,47 @@ impl ThreadRepositoryTrait for ThreadRepository {
         Ok(thread)
     }
 
+    // Deleted threads that still have replies are kept as tombstones.
+    async fn get_visible_thread_by_id(
+        &self,
+        id: i64,
+    ) -> RepositoryResult<ResponseThread> {
+        let thread = sqlx::query_as::<_, ResponseThread>(
+            r#"
+            SELECT
+                t.*,
+                (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
+                COALESCE(MAX(v.view_count), 0) AS views,
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = $1) AS reply_count
+            FROM thread t
+            LEFT JOIN votes u ON u.thread_id = t.id
+            LEFT JOIN views v ON v.thread_id = t.id
+            WHERE t.id = $1
+            AND (t.is_deleted = FALSE OR EXISTS (
+                SELECT 1 FROM thread r WHERE r.parent_thread = t.id AND r.is_deleted = FALSE
+            ))
+            GROUP BY t.id
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&*self.conn)
+        .await?;
+
+        Ok(thread)
+    }
+
+    async fn find_deleted_thread(&self, id: i64) -> RepositoryResult<DeletedThread> {
+        let thread = sqlx::query_as::<_, DeletedThread>(
+            "SELECT deleted_at, deleted_by, updated_at FROM thread
+             WHERE id = $1 AND is_deleted = TRUE",
+        )
+        .bind(id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(thread)
+    }
+
     async fn list_thread_by_user_id(
         &self,
         user_id: i64,
@@ -127,8 +340,7 @@ impl ThreadRepositoryTrait for ThreadRepository {
             LEFT JOIN votes u ON u.thread_id = t.id
             LEFT JOIN views v ON v.thread_id = t.id
             WHERE t.user_id = $1
-            AND t.created_at < $2
-            AND t.id > $3
+            AND ($2::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($2, $3))
             AND t.is_deleted = FALSE
             GROUP BY t.id
             ORDER BY t.created_at DESC, t.id DESC
@@ -136,7 +348,7 @@ impl ThreadRepositoryTrait for ThreadRepository {
             "#,
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
         .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
@@ -145,33 +357,105 @@ impl ThreadRepositoryTrait for ThreadRepository {
         Ok(thread_list)
     }
 
+    // Applies the edit only if the thread is still at `version`, `None` otherwise.
+    // The new content is stored as the next revision (the posted content is 1),
+    // `mentions` replace the stored ones only when the content is edited.
     async fn update_thread(
         &self,
         id: i64,
+        editor_id: i64,
+        version: i32,
         new_thread: RequestUpdateThread,
-    ) -> RepositoryResult<ResponseThread> {
-        let affected_rows = sqlx::query(
-            "UPDATE thread SET title = $1, content = $2, parent_thread = $3 WHERE id = $4",
+        mentions: &[ThreadMention],
+    ) -> RepositoryResult<Option<ResponseThread>> {
+        let mut tx = self.conn.begin().await?;
+
+        let updated = sqlx::query_as::<_, (i32, Option<String>, String)>(
+            r#"
+            UPDATE thread
+            SET title = COALESCE($1, title), content = COALESCE($2, content),
+                edit_count = edit_count + 1, updated_at = NOW()
+            WHERE id = $3
+            AND version = $4
+            AND is_deleted = FALSE
+            RETURNING version, title, content
+            "#,
         )
         .bind(&new_thread.title)
         .bind(&new_thread.content)
-        .bind(&new_thread.parent_thread)
+        .bind(id)
+        .bind(version)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((revision, title, content)) = updated else {
+            return Ok(None);
+        };
+
+        let _ = sqlx::query(
+            "INSERT INTO thread_revisions (thread_id, revision, editor_id, title, content) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(id)
+        .bind(revision)
+        .bind(editor_id)
+        .bind(title)
+        .bind(&content)
+        .execute(&mut *tx)
+        .await?;
+
+        if new_thread.content.is_some() {
+            save_thread_tags(&mut tx, id, &content).await?;
+            save_thread_mentions(&mut tx, id, mentions).await?;
+        }
+
+        tx.commit().await?;
+        let thread = self.get_thread_by_id(id).await?;
+        Ok(Some(thread))
+    }
+
+    async fn list_revision(
+        &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ThreadRevision>> {
+        let revisions = sqlx::query_as::<_, ThreadRevision>(
+            r#"
+            SELECT thread_id, revision, editor_id, title, content, created_at
+            FROM thread_revisions
+            WHERE thread_id = $1
+            AND revision > $2
+            ORDER BY revision ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(thread_id)
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(revisions)
+    }
+
+    async fn delete_thread(&self, id: i64, deleted_by: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE thread SET is_deleted = TRUE, deleted_at = NOW(), deleted_by = $1 WHERE id = $2 AND is_deleted = FALSE",
+        )
+        .bind(deleted_by)
         .bind(id)
         .execute(&*self.conn)
         .await?
         .rows_affected();
 
         if affected_rows > 0 {
-            let thread = self.get_thread_by_id(id).await?;
-            Ok(thread)
+            Ok(true)
         } else {
             Err(CustomError::NotFound)
         }
     }
 
-    async fn delete_thread(&self, id: i64) -> RepositoryResult<bool> {
+    async fn restore_thread(&self, id: i64) -> RepositoryResult<bool> {
         let affected_rows = sqlx::query(
-            "UPDATE thread SET is_deleted = TRUE, deleted_at = NOW() WHERE id = $1",
+            "UPDATE thread SET is_deleted = FALSE, deleted_at = NULL, deleted_by = NULL WHERE id = $1 AND is_deleted = TRUE",
         )
         .bind(id)
         .execute(&*self.conn)
@@ -185,11 +469,63 @@ impl ThreadRepositoryTrait for ThreadRepository {
         }
     }
 
+    // Hard-deletes the threads deleted before `deleted_before`. A tombstone with
+    // replies cannot be removed (they would cascade), so only its content and
+    // revisions are wiped; it is removed by a later run once its replies are gone.
+    async fn purge_deleted_thread(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> RepositoryResult<u64> {
+        let mut tx = self.conn.begin().await?;
+
+        let purged_rows = sqlx::query(
+            r#"
+            DELETE FROM thread t
+            WHERE t.is_deleted = TRUE
+            AND t.deleted_at < $1
+            AND NOT EXISTS (SELECT 1 FROM thread r WHERE r.parent_thread = t.id)
+            "#,
+        )
+        .bind(deleted_before)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let _ = sqlx::query(
+            r#"
+            DELETE FROM thread_revisions rev
+            USING thread t
+            WHERE rev.thread_id = t.id
+            AND t.is_deleted = TRUE
+            AND t.deleted_at < $1
+            "#,
+        )
+        .bind(deleted_before)
+        .execute(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query(
+            r#"
+            UPDATE thread SET title = NULL, content = ''
+            WHERE is_deleted = TRUE
+            AND deleted_at < $1
+            AND content <> ''
+            "#,
+        )
+        .bind(deleted_before)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(purged_rows)
+    }
+
     async fn list_thread_by_following(
         &self,
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> RepositoryResult<Vec<ResponseThread>> {
         let thread_list = sqlx::query_as::<_, ResponseThread>(
             r#"
@@ -206,57 +542,393 @@ impl ThreadRepositoryTrait for ThreadRepository {
             WHERE f.user_id = $1
             AND t.is_deleted = FALSE
             AND t.parent_thread IS NULL
-            AND t.created_at < $2
+            AND ($2::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($2, $3))
+            AND t.id <> ALL($5)
             GROUP BY t.id
-            ORDER BY t.created_at DESC
-            LIMIT $3;
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $4;
             "#,
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
+        .bind(excluded_thread_ids)
         .fetch_all(&*self.conn)
         .await?;
 
         Ok(thread_list)
     }
 
-    async fn list_thread_by_popularity_score(
+    async fn list_thread_by_latest_created(
         &self,
         cursor: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> RepositoryResult<Vec<ResponseThread>> {
         let thread_list = sqlx::query_as::<_, ResponseThread>(
             r#"
-            SELECT 
+            SELECT
                 t.*,
                 (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
                 COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
                 COALESCE(MAX(v.view_count), 0) AS views,
-                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count,
-                (COALESCE(COUNT(u.thread_id), 0) * 2 + COALESCE(MAX(v.view_count), 0) * 0.5) 
-                / POW((EXTRACT(EPOCH FROM CURRENT_TIMESTAMP) - EXTRACT(EPOCH FROM t.created_at)) / 3600.0 + 2, 1.5) AS adj_score
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count
             FROM thread t
-            LEFT JOIN votes u ON u.thread_id = t.id AND u.reaction = 'UP'
+            LEFT JOIN votes u ON u.thread_id = t.id
             LEFT JOIN views v ON v.thread_id = t.id
-            WHERE t.created_at < $1
+            WHERE ($1::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($1, $2))
             AND t.parent_thread IS NULL
             AND t.is_deleted = FALSE
-            GROUP BY t.id, t.created_at
-            ORDER BY adj_score DESC, t.created_at DESC
-            LIMIT $2
-            "#
+            AND t.id <> ALL($4)
+            GROUP BY t.id
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $3
+            "#,
         )
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
+        .bind(excluded_thread_ids)
         .fetch_all(&*self.conn)
         .await?;
 
         Ok(thread_list)
     }
 
-    async fn list_thread_by_latest_created(
+    // Replies of `thread_id` down to `max_depth` levels, at most `per_level` replies
+    // per thread (oldest first). Only the direct replies are paged by the cursor.
+    // A deleted reply is kept as long as a live reply is somewhere below it.
+    async fn list_reply_tree(
         &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        max_depth: i32,
+        per_level: i64,
+    ) -> RepositoryResult<Vec<ThreadTreeRow>> {
+        let tree = sqlx::query_as::<_, ThreadTreeRow>(
+            r#"
+            WITH RECURSIVE tree AS (
+                SELECT t.id, 0 AS depth
+                FROM thread t
+                WHERE t.id = $1
+                UNION ALL
+                SELECT c.id, tree.depth + 1
+                FROM tree
+                CROSS JOIN LATERAL (
+                    SELECT t.id
+                    FROM thread t
+                    WHERE t.parent_thread = tree.id
+                    AND (t.is_deleted = FALSE OR EXISTS (
+                        WITH RECURSIVE descendants AS (
+                            SELECT d.id, d.is_deleted FROM thread d WHERE d.parent_thread = t.id
+                            UNION ALL
+                            SELECT d.id, d.is_deleted
+                            FROM thread d
+                            JOIN descendants ON d.parent_thread = descendants.id
+                        )
+                        SELECT 1 FROM descendants WHERE descendants.is_deleted = FALSE
+                    ))
+                    AND (tree.depth > 0 OR $2::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) > ($2, $3))
+                    ORDER BY t.created_at, t.id
+                    LIMIT $5
+                ) c
+                WHERE tree.depth < $4
+            )
+            SELECT
+                t.*,
+                (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
+                COALESCE(MAX(v.view_count), 0) AS views,
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count,
+                tree.depth
+            FROM tree
+            JOIN thread t ON t.id = tree.id
+            LEFT JOIN votes u ON u.thread_id = t.id
+            LEFT JOIN views v ON v.thread_id = t.id
+            WHERE tree.depth > 0
+            GROUP BY t.id, tree.depth
+            ORDER BY tree.depth, t.created_at, t.id
+            "#,
+        )
+        .bind(thread_id)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
+        .bind(max_depth)
+        .bind(per_level)
+        .fetch_all(&*self.conn)
+        .await?;
+
+        Ok(tree)
+    }
+
+    // Parents of `thread_id` up to the root, root first. Deleted ancestors are kept.
+    // Replies cannot be moved, `path` only stops the walk on a cycle left by bad data.
+    async fn list_ancestor(
+        &self,
+        thread_id: i64,
+    ) -> RepositoryResult<Vec<ResponseThread>> {
+        let ancestors = sqlx::query_as::<_, ResponseThread>(
+            r#"
+            WITH RECURSIVE ancestors AS (
+                SELECT p.id, p.parent_thread, 1 AS depth, ARRAY[$1::BIGINT, p.id] AS path
+                FROM thread p
+                WHERE p.id = (SELECT parent_thread FROM thread WHERE id = $1)
+                UNION ALL
+                SELECT p.id, p.parent_thread, a.depth + 1, a.path || p.id
+                FROM thread p
+                JOIN ancestors a ON p.id = a.parent_thread
+                WHERE NOT p.id = ANY(a.path)
+            )
+            SELECT
+                t.*,
+                (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
+                COALESCE(MAX(v.view_count), 0) AS views,
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count
+            FROM ancestors a
+            JOIN thread t ON t.id = a.id
+            LEFT JOIN votes u ON u.thread_id = t.id
+            LEFT JOIN views v ON v.thread_id = t.id
+            GROUP BY t.id, a.depth
+            ORDER BY a.depth DESC
+            "#,
+        )
+        .bind(thread_id)
+        .fetch_all(&*self.conn)
+        .await?;
+
+        Ok(ancestors)
+    }
+
+    // The snapshot of `parent_thread` for the bucket, ranked with its first `size`
+    // threads by the first request of the bucket. Concurrent first requests meet on
+    // the unique bucket index, only one of them ranks the list.
+    async fn find_or_create_snapshot(
+        &self,
+        parent_thread: Option<i64>,
+        bucket_at: DateTime<Utc>,
+        size: i64,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<ThreadSnapshot> {
+        let find_bucket = || {
+            sqlx::query_as::<_, ThreadSnapshot>(
+                r#"
+                SELECT id, parent_thread, entry_count, is_complete
+                FROM thread_snapshots
+                WHERE COALESCE(parent_thread, 0) = COALESCE($1, 0)
+                AND bucket_at = $2
+                AND expires_at > NOW()
+                "#,
+            )
+            .bind(parent_thread)
+            .bind(bucket_at)
+        };
+        if let Some(snapshot) = find_bucket().fetch_optional(&*self.conn).await? {
+            return Ok(snapshot);
+        }
+
+        let mut tx = self.conn.begin().await?;
+        let snapshot_id = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO thread_snapshots (parent_thread, bucket_at, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (COALESCE(parent_thread, 0), bucket_at) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(parent_thread)
+        .bind(bucket_at)
+        .bind(expires_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(snapshot_id) = snapshot_id else {
+            // another request ranked this bucket in the meantime
+            tx.rollback().await?;
+            return Ok(find_bucket().fetch_one(&*self.conn).await?);
+        };
+
+        sqlx::query("DELETE FROM thread_snapshots WHERE expires_at < NOW()")
+            .execute(&mut *tx)
+            .await?;
+        let snapshot = ThreadSnapshot {
+            id: snapshot_id,
+            parent_thread,
+            entry_count: 0,
+            is_complete: false,
+        };
+        let snapshot = rank_snapshot_chunk(&mut tx, snapshot, size).await?;
+
+        tx.commit().await?;
+        Ok(snapshot)
+    }
+
+    // Ranks the next `size` threads, the ones already in the snapshot are left out so
+    // a thread is never listed twice. Ranked positions never change afterwards.
+    async fn extend_snapshot(
+        &self,
+        snapshot_id: i64,
+        size: i64,
+    ) -> RepositoryResult<ThreadSnapshot> {
+        let mut tx = self.conn.begin().await?;
+        // the lock keeps concurrent requests from ranking the same chunk twice
+        let snapshot = sqlx::query_as::<_, ThreadSnapshot>(
+            r#"
+            SELECT id, parent_thread, entry_count, is_complete
+            FROM thread_snapshots
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(snapshot_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if snapshot.is_complete {
+            return Ok(snapshot);
+        }
+        let snapshot = rank_snapshot_chunk(&mut tx, snapshot, size).await?;
+
+        tx.commit().await?;
+        Ok(snapshot)
+    }
+
+    async fn find_snapshot(&self, id: i64) -> RepositoryResult<ThreadSnapshot> {
+        let snapshot = sqlx::query_as::<_, ThreadSnapshot>(
+            r#"
+            SELECT id, parent_thread, entry_count, is_complete
+            FROM thread_snapshots
+            WHERE id = $1 AND expires_at > NOW()
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(snapshot)
+    }
+
+    // Threads ranked up to `max_position`, whatever happened to them since.
+    async fn list_snapshot_thread_id(
+        &self,
+        snapshot_id: i64,
+        max_position: i64,
+    ) -> RepositoryResult<Vec<i64>> {
+        let thread_ids = sqlx::query_scalar::<_, i64>(
+            "SELECT thread_id FROM thread_snapshot_entries WHERE snapshot_id = $1 AND position <= $2",
+        )
+        .bind(snapshot_id)
+        .bind(max_position)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(thread_ids)
+    }
+
+    // Threads keep their rank from the snapshot, their votes and views are current.
+    async fn list_thread_by_snapshot(
+        &self,
+        snapshot_id: i64,
+        after_position: i64,
+        limit: i64,
+    ) -> RepositoryResult<Vec<RankedThread>> {
+        let thread_list = sqlx::query_as::<_, RankedThread>(
+            r#"
+            SELECT
+                t.*,
+                (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
+                COALESCE(MAX(v.view_count), 0) AS views,
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count,
+                e.position
+            FROM thread_snapshot_entries e
+            JOIN thread t ON t.id = e.thread_id
+            LEFT JOIN votes u ON u.thread_id = t.id
+            LEFT JOIN views v ON v.thread_id = t.id
+            WHERE e.snapshot_id = $1
+            AND e.position > $2
+            AND (t.is_deleted = FALSE OR (t.parent_thread IS NOT NULL AND EXISTS (
+                SELECT 1 FROM thread r WHERE r.parent_thread = t.id AND r.is_deleted = FALSE
+            )))
+            GROUP BY t.id, e.position
+            ORDER BY e.position ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(after_position)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+
+        Ok(thread_list)
+    }
+
+    // Matches of `filter.query` by relevance, title matches rank first.
+    async fn search_thread(
+        &self,
+        filter: ThreadSearchFilter,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ThreadSearchRow>> {
+        let is_reply =
+            filter.thread_type.map(|thread_type| thread_type == ThreadSearchType::Reply);
+        let thread_list = sqlx::query_as::<_, ThreadSearchRow>(
+            r#"
+            WITH query AS (
+                SELECT websearch_to_tsquery('english', $1) AS tsq
+            ),
+            matched AS (
+                SELECT
+                    t.*,
+                    ts_rank(t.search_vector, query.tsq) AS rank,
+                    (SELECT
+                        COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                        COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)
+                    FROM votes u WHERE u.thread_id = t.id) AS votes,
+                    (SELECT COALESCE(MAX(v.view_count), 0)
+                    FROM views v WHERE v.thread_id = t.id) AS views,
+                    (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count
+                FROM thread t
+                CROSS JOIN query
+                WHERE t.search_vector @@ query.tsq
+                AND t.is_deleted = FALSE
+                AND ($2::TEXT IS NULL OR t.user_id = (SELECT id FROM users WHERE handle = $2))
+                AND ($3::TIMESTAMPTZ IS NULL OR t.created_at >= $3)
+                AND ($4::TIMESTAMPTZ IS NULL OR t.created_at < $4)
+                AND ($5::BOOLEAN IS NULL OR (t.parent_thread IS NOT NULL) = $5)
+            )
+            SELECT
+                m.*,
+                CASE WHEN m.title IS NULL THEN NULL
+                ELSE ts_headline('english', html_escape(m.title), query.tsq,
+                    'StartSel=<mark>, StopSel=</mark>, HighlightAll=TRUE')
+                END AS title_highlight,
+                ts_headline('english', html_escape(m.content), query.tsq,
+                    'StartSel=<mark>, StopSel=</mark>, MaxWords=35, MinWords=15, MaxFragments=2, FragmentDelimiter=" ... "') AS snippet
+            FROM matched m
+            CROSS JOIN query
+            WHERE ($6::BIGINT IS NULL OR m.votes >= $6)
+            AND ($7::REAL IS NULL OR (m.rank, m.id) < ($7, $8))
+            ORDER BY m.rank DESC, m.id DESC
+            LIMIT $9
+            "#,
+        )
+        .bind(filter.query)
+        .bind(filter.author)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(is_reply)
+        .bind(filter.min_votes)
+        .bind(cursor.rank)
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+
+        Ok(thread_list)
+    }
+
+    async fn list_thread_by_tag(
+        &self,
+        tag: &str,
         cursor: CursorClaims,
         limit: i64,
     ) -> RepositoryResult<Vec<ResponseThread>> {
@@ -269,17 +941,21 @@ impl ThreadRepositoryTrait for ThreadRepository {
                 COALESCE(MAX(v.view_count), 0) AS views,
                 (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count
             FROM thread t
+            JOIN thread_tags tt ON tt.thread_id = t.id
+            JOIN tags tg ON tg.id = tt.tag_id
             LEFT JOIN votes u ON u.thread_id = t.id
             LEFT JOIN views v ON v.thread_id = t.id
-            WHERE t.created_at < $1
-            AND t.parent_thread IS NULL
+            WHERE tg.name = $1
             AND t.is_deleted = FALSE
+            AND ($2::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($2, $3))
             GROUP BY t.id
-            ORDER BY created_at DESC
-            LIMIT $2
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $4
             "#,
         )
-        .bind(cursor.created_at)
+        .bind(tag)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
@@ -287,37 +963,185 @@ impl ThreadRepositoryTrait for ThreadRepository {
         Ok(thread_list)
     }
 
-    async fn list_subthread_by_parent_id(
+    // Every thread counts for its tags with the popularity score of
+    // `rank_snapshot_chunk`, up votes and views decayed by the age in hours, plus one
+    // so a fresh thread without reactions still counts.
+    async fn list_trending_tag(
         &self,
-        thread_id: i64,
+        created_after: DateTime<Utc>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<TrendingTag>> {
+        let tag_list = sqlx::query_as::<_, TrendingTag>(
+            r#"
+            SELECT
+                tg.name,
+                COUNT(*) AS thread_count,
+                SUM(
+                    (1
+                    + (SELECT COUNT(*) FROM votes u WHERE u.thread_id = t.id AND u.reaction = 'UP') * 2
+                    + (SELECT COALESCE(MAX(v.view_count), 0) FROM views v WHERE v.thread_id = t.id) * 0.5)
+                    / POW((EXTRACT(EPOCH FROM CURRENT_TIMESTAMP) - EXTRACT(EPOCH FROM t.created_at)) / 3600.0 + 2, 1.5)
+                )::FLOAT8 AS score
+            FROM thread_tags tt
+            JOIN tags tg ON tg.id = tt.tag_id
+            JOIN thread t ON t.id = tt.thread_id
+            WHERE t.created_at >= $1
+            AND t.is_deleted = FALSE
+            GROUP BY tg.id
+            ORDER BY score DESC, thread_count DESC, tg.name ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(created_after)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+
+        Ok(tag_list)
+    }
+
+    // Mentions of users deleted since are left out, they read as plain text.
+    async fn list_mention(
+        &self,
+        thread_ids: &[i64],
+    ) -> RepositoryResult<Vec<ThreadMentionRow>> {
+        let mentions = sqlx::query_as::<_, ThreadMentionRow>(
+            r#"
+            SELECT
+                m.thread_id, m.user_id, u.handle,
+                m.start_offset AS start, m.end_offset AS end
+            FROM thread_mentions m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.thread_id = ANY($1)
+            AND u.is_deleted = FALSE
+            ORDER BY m.thread_id, m.start_offset ASC
+            "#,
+        )
+        .bind(thread_ids)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(mentions)
+    }
+
+    async fn list_thread_by_mentioned_user(
+        &self,
+        user_id: i64,
         cursor: CursorClaims,
         limit: i64,
     ) -> RepositoryResult<Vec<ResponseThread>> {
-        let subthread_list = sqlx::query_as::<_, ResponseThread>(
+        let thread_list = sqlx::query_as::<_, ResponseThread>(
             r#"
             SELECT
                 t.*,
                 (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
                 COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes,
                 COALESCE(MAX(v.view_count), 0) AS views,
-                (SELECT COUNT(*) FROM thread WHERE parent_thread = $1) AS reply_count
+                (SELECT COUNT(*) FROM thread WHERE parent_thread = t.id) AS reply_count
             FROM thread t
             LEFT JOIN votes u ON u.thread_id = t.id
             LEFT JOIN views v ON v.thread_id = t.id
-            WHERE t.parent_thread = $1
-            AND t.created_at < $2
+            WHERE EXISTS (
+                SELECT 1 FROM thread_mentions m WHERE m.thread_id = t.id AND m.user_id = $1
+            )
             AND t.is_deleted = FALSE
+            AND ($2::TIMESTAMPTZ IS NULL OR (t.created_at, t.id) < ($2, $3))
             GROUP BY t.id
-            ORDER BY votes DESC, created_at DESC
-            LIMIT $3
+            ORDER BY t.created_at DESC, t.id DESC
+            LIMIT $4
             "#,
         )
-        .bind(thread_id)
-        .bind(cursor.created_at)
+        .bind(user_id)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
 
-        Ok(subthread_list)
+        Ok(thread_list)
     }
 }
+
+// Ranks the top-level threads by popularity, or the replies of `parent_thread` by
+// votes, and appends the next `size` of them after the ones already ranked.
+async fn rank_snapshot_chunk(
+    conn: &mut PgConnection,
+    snapshot: ThreadSnapshot,
+    size: i64,
+) -> RepositoryResult<ThreadSnapshot> {
+    let ranking = match snapshot.parent_thread {
+        None => sqlx::query(
+            r#"
+            INSERT INTO thread_snapshot_entries (snapshot_id, position, thread_id)
+            SELECT $1, $2 + ROW_NUMBER() OVER (ORDER BY adj_score DESC, created_at DESC, id DESC), id
+            FROM (
+                SELECT
+                    t.id,
+                    t.created_at,
+                    (COALESCE(COUNT(u.thread_id), 0) * 2 + COALESCE(MAX(v.view_count), 0) * 0.5)
+                    / POW((EXTRACT(EPOCH FROM CURRENT_TIMESTAMP) - EXTRACT(EPOCH FROM t.created_at)) / 3600.0 + 2, 1.5) AS adj_score
+                FROM thread t
+                LEFT JOIN votes u ON u.thread_id = t.id AND u.reaction = 'UP'
+                LEFT JOIN views v ON v.thread_id = t.id
+                WHERE t.parent_thread IS NULL
+                AND t.is_deleted = FALSE
+                AND NOT EXISTS (
+                    SELECT 1 FROM thread_snapshot_entries e
+                    WHERE e.snapshot_id = $1 AND e.thread_id = t.id
+                )
+                GROUP BY t.id
+                ORDER BY adj_score DESC, t.created_at DESC, t.id DESC
+                LIMIT $3
+            ) ranked
+            "#,
+        )
+        .bind(snapshot.id)
+        .bind(snapshot.entry_count)
+        .bind(size),
+        Some(parent_thread) => sqlx::query(
+            r#"
+            INSERT INTO thread_snapshot_entries (snapshot_id, position, thread_id)
+            SELECT $1, $2 + ROW_NUMBER() OVER (ORDER BY votes DESC, created_at DESC, id DESC), id
+            FROM (
+                SELECT
+                    t.id,
+                    t.created_at,
+                    (COALESCE(SUM(CASE WHEN u.reaction = 'UP' THEN 1 ELSE 0 END), 0) +
+                    COALESCE(SUM(CASE WHEN u.reaction = 'DOWN' THEN -1 ELSE 0 END), 0)) AS votes
+                FROM thread t
+                LEFT JOIN votes u ON u.thread_id = t.id
+                WHERE t.parent_thread = $4
+                AND (t.is_deleted = FALSE OR EXISTS (
+                    SELECT 1 FROM thread r WHERE r.parent_thread = t.id AND r.is_deleted = FALSE
+                ))
+                AND NOT EXISTS (
+                    SELECT 1 FROM thread_snapshot_entries e
+                    WHERE e.snapshot_id = $1 AND e.thread_id = t.id
+                )
+                GROUP BY t.id
+                ORDER BY votes DESC, t.created_at DESC, t.id DESC
+                LIMIT $3
+            ) ranked
+            "#,
+        )
+        .bind(snapshot.id)
+        .bind(snapshot.entry_count)
+        .bind(size)
+        .bind(parent_thread),
+    };
+    let ranked = ranking.execute(&mut *conn).await?.rows_affected() as i64;
+
+    let snapshot = sqlx::query_as::<_, ThreadSnapshot>(
+        r#"
+        UPDATE thread_snapshots
+        SET entry_count = entry_count + $2, is_complete = $3
+        WHERE id = $1
+        RETURNING id, parent_thread, entry_count, is_complete
+        "#,
+    )
+    .bind(snapshot.id)
+    .bind(ranked)
+    .bind(ranked < size)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(snapshot)
+}
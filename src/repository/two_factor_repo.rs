@@ -0,0 +1,165 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::{domain::model::two_factor::RecoveryCode, error::CustomError};
+
+#[async_trait]
+pub trait TwoFactorRepositoryTrait: Send + Sync {
+    async fn set_pending_secret(
+        &self,
+        user_id: i64,
+        secret: &str,
+    ) -> RepositoryResult<()>;
+    async fn enable_totp(
+        &self,
+        user_id: i64,
+        used_step: i64,
+        recovery_code_hashes: Vec<String>,
+    ) -> RepositoryResult<()>;
+    async fn disable_totp(&self, user_id: i64) -> RepositoryResult<()>;
+    async fn update_last_used_step(
+        &self,
+        user_id: i64,
+        used_step: i64,
+    ) -> RepositoryResult<bool>;
+    async fn list_unused_recovery_code(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<RecoveryCode>>;
+    async fn consume_recovery_code(&self, id: i64) -> RepositoryResult<bool>;
+}
+
+pub struct TwoFactorRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl TwoFactorRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl TwoFactorRepositoryTrait for TwoFactorRepository {
+    async fn set_pending_secret(
+        &self,
+        user_id: i64,
+        secret: &str,
+    ) -> RepositoryResult<()> {
+        let affected_rows = sqlx::query(
+            "UPDATE users SET totp_secret = $1, totp_last_used_step = NULL WHERE id = $2 AND is_totp_enabled = FALSE",
+        )
+        .bind(secret)
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        if affected_rows > 0 {
+            Ok(())
+        } else {
+            Err(CustomError::NotFound)
+        }
+    }
+
+    async fn enable_totp(
+        &self,
+        user_id: i64,
+        used_step: i64,
+        recovery_code_hashes: Vec<String>,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.conn.begin().await?;
+
+        let _ = sqlx::query(
+            "UPDATE users SET is_totp_enabled = TRUE, totp_last_used_step = $1 WHERE id = $2",
+        )
+        .bind(used_step)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let _ = sqlx::query(
+            "INSERT INTO recovery_codes (user_id, code_hash) SELECT $1, UNNEST($2::TEXT[])",
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn disable_totp(&self, user_id: i64) -> RepositoryResult<()> {
+        let mut tx = self.conn.begin().await?;
+
+        let _ = sqlx::query(
+            "UPDATE users SET is_totp_enabled = FALSE, totp_secret = NULL, totp_last_used_step = NULL WHERE id = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Only moves forward, so a code cannot be replayed within its validity window.
+    async fn update_last_used_step(
+        &self,
+        user_id: i64,
+        used_step: i64,
+    ) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            r#"
+            UPDATE users SET totp_last_used_step = $1
+            WHERE id = $2
+            AND (totp_last_used_step IS NULL OR totp_last_used_step < $1)
+            "#,
+        )
+        .bind(used_step)
+        .bind(user_id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+
+    async fn list_unused_recovery_code(
+        &self,
+        user_id: i64,
+    ) -> RepositoryResult<Vec<RecoveryCode>> {
+        let recovery_codes = sqlx::query_as::<_, RecoveryCode>(
+            "SELECT * FROM recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(recovery_codes)
+    }
+
+    async fn consume_recovery_code(&self, id: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE recovery_codes SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+        )
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+}
@@ -5,16 +5,25 @@ use std::sync::Arc;
 use super::RepositoryResult;
 use crate::{
     domain::{
-        dto::user::{RequestSignup, RequestUpsertProfile},
-        model::user::User,
+        dto::{
+            search::ResponseUserSearchResult,
+            user::{RequestUpsertProfile, ResponseAdminUser},
+        },
+        model::{
+            cursor_claims::CursorClaims,
+            user::{User, UserRole},
+        },
     },
     error::CustomError,
-    utils::crypto,
 };
 
 #[async_trait]
 pub trait UserRepositoryTrait: Send + Sync {
-    async fn create_user(&self, new_user: RequestSignup) -> Result<String, CustomError>;
+    async fn create_user(
+        &self,
+        email: &str,
+        hash_password: &str,
+    ) -> RepositoryResult<User>;
     async fn find_user_generic(
         &self,
         column: &str,
@@ -28,6 +37,23 @@ pub trait UserRepositoryTrait: Send + Sync {
         id: i64,
         new_profile: RequestUpsertProfile,
     ) -> RepositoryResult<User>;
+    async fn update_password(&self, id: i64, hash_password: &str)
+        -> RepositoryResult<()>;
+    async fn mark_email_verified(&self, id: i64) -> RepositoryResult<()>;
+    async fn list_user(
+        &self,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ResponseAdminUser>>;
+    async fn update_role(&self, id: i64, role: UserRole) -> RepositoryResult<()>;
+    async fn update_suspended(&self, id: i64, is_suspended: bool)
+        -> RepositoryResult<()>;
+    async fn search_user(
+        &self,
+        query: &str,
+        searcher_id: Option<i64>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ResponseUserSearchResult>>;
 }
 
 pub struct UserRepository {
@@ -42,19 +68,24 @@ impl UserRepository {
 
 #[async_trait]
 impl UserRepositoryTrait for UserRepository {
-    async fn create_user(&self, new_user: RequestSignup) -> Result<String, CustomError> {
-        if self.find_user_by_email(&new_user.email).await.is_ok() {
-            return Err(CustomError::AlreadyRegisteredUser(new_user.email));
+    async fn create_user(
+        &self,
+        email: &str,
+        hash_password: &str,
+    ) -> RepositoryResult<User> {
+        if self.find_user_by_email(email).await.is_ok() {
+            return Err(CustomError::AlreadyRegisteredUser(email.to_string()));
         }
 
-        let hashed_password = crypto::hash_password(&new_user.password)?;
-        let _ = sqlx::query("INSERT INTO users (email, hash_password) VALUES ($1, $2)")
-            .bind(&new_user.email)
-            .bind(&hashed_password)
-            .execute(&*self.conn)
-            .await?;
+        let user = sqlx::query_as::<_, User>(
+            "INSERT INTO users (email, hash_password) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(email)
+        .bind(hash_password)
+        .fetch_one(&*self.conn)
+        .await?;
 
-        Ok("User created successfully".to_string())
+        Ok(user)
     }
 
     async fn find_user_by_email(&self, email: &str) -> RepositoryResult<User> {
@@ -88,7 +119,7 @@ impl UserRepositoryTrait for UserRepository {
         };
 
         let user =
-            sqlx::query_as::<_, User>(&query).bind(value).fetch_one(&*self.conn).await?;
+            sqlx::query_as::<_, User>(query).bind(value).fetch_one(&*self.conn).await?;
         Ok(user)
     }
 
@@ -123,4 +154,154 @@ impl UserRepositoryTrait for UserRepository {
 
         Ok(updated_user)
     }
+
+    async fn update_password(
+        &self,
+        id: i64,
+        hash_password: &str,
+    ) -> RepositoryResult<()> {
+        let affected_rows = sqlx::query(
+            "UPDATE users SET hash_password = $1, updated_at = NOW() WHERE id = $2 AND is_deleted = FALSE",
+        )
+        .bind(hash_password)
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        if affected_rows > 0 {
+            Ok(())
+        } else {
+            Err(CustomError::NotFound)
+        }
+    }
+
+    async fn mark_email_verified(&self, id: i64) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            "UPDATE users SET is_email_verified = TRUE, email_verified_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_user(
+        &self,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ResponseAdminUser>> {
+        let user_list = sqlx::query_as::<_, ResponseAdminUser>(
+            r#"
+            SELECT id, email, handle, role, is_email_verified, is_suspended, suspended_at, created_at
+            FROM users
+            WHERE id > $1
+            AND is_deleted = FALSE
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(user_list)
+    }
+
+    async fn update_role(&self, id: i64, role: UserRole) -> RepositoryResult<()> {
+        let affected_rows = sqlx::query(
+            "UPDATE users SET role = $1, updated_at = NOW() WHERE id = $2 AND is_deleted = FALSE",
+        )
+        .bind(role)
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        if affected_rows > 0 {
+            Ok(())
+        } else {
+            Err(CustomError::NotFound)
+        }
+    }
+
+    async fn update_suspended(
+        &self,
+        id: i64,
+        is_suspended: bool,
+    ) -> RepositoryResult<()> {
+        let affected_rows = sqlx::query(
+            r#"
+            UPDATE users SET
+                is_suspended = $1,
+                suspended_at = CASE WHEN $1 THEN NOW() ELSE NULL END,
+                updated_at = NOW()
+            WHERE id = $2
+            AND is_deleted = FALSE
+            "#,
+        )
+        .bind(is_suspended)
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        if affected_rows > 0 {
+            Ok(())
+        } else {
+            Err(CustomError::NotFound)
+        }
+    }
+
+    // `query` is lowercase, without the leading `@` of handles and with the LIKE
+    // wildcards escaped. Prefix matches come first, then the users the searcher
+    // follows, then the most followed.
+    async fn search_user(
+        &self,
+        query: &str,
+        searcher_id: Option<i64>,
+        limit: i64,
+    ) -> RepositoryResult<Vec<ResponseUserSearchResult>> {
+        let user_list = sqlx::query_as::<_, ResponseUserSearchResult>(
+            r#"
+            WITH matched AS (
+                SELECT
+                    u.id, u.name, u.handle, u.profile_img_url,
+                    -- `follow.user_id` follows `follow.follower_id`
+                    (SELECT COUNT(*) FROM follow f WHERE f.follower_id = u.id) AS follower_count,
+                    EXISTS (
+                        SELECT 1 FROM follow f WHERE f.follower_id = u.id AND f.user_id = $2
+                    ) AS is_following,
+                    (LOWER(u.handle) LIKE '@' || $1 || '%'
+                        OR LOWER(u.name) LIKE $1 || '%'
+                        OR LOWER(u.name) LIKE '% ' || $1 || '%') AS is_prefix,
+                    GREATEST(
+                        similarity(LOWER(u.handle), $1),
+                        similarity(LOWER(u.name), $1)
+                    ) AS score
+                FROM users u
+                WHERE u.is_deleted = FALSE
+                AND u.is_suspended = FALSE
+                AND u.is_profile_complete = TRUE
+                AND (
+                    LOWER(u.handle) LIKE '@' || $1 || '%'
+                    OR LOWER(u.name) LIKE $1 || '%'
+                    OR LOWER(u.name) LIKE '% ' || $1 || '%'
+                    OR LOWER(u.handle) % $1
+                    OR LOWER(u.name) % $1
+                )
+            )
+            SELECT id, name, handle, profile_img_url, follower_count, is_following
+            FROM matched
+            ORDER BY is_prefix DESC, is_following DESC, follower_count DESC, score DESC, id ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(query)
+        .bind(searcher_id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(user_list)
+    }
 }
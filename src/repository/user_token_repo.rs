@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::domain::model::user_token::{TokenPurpose, UserToken};
+
+#[async_trait]
+pub trait UserTokenRepositoryTrait: Send + Sync {
+    async fn create_token(
+        &self,
+        user_id: i64,
+        purpose: TokenPurpose,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<()>;
+    async fn find_valid_token(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+    ) -> RepositoryResult<UserToken>;
+    async fn find_latest_token(
+        &self,
+        user_id: i64,
+        purpose: TokenPurpose,
+    ) -> RepositoryResult<Option<UserToken>>;
+    async fn consume_token(&self, id: i64) -> RepositoryResult<bool>;
+}
+
+pub struct UserTokenRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl UserTokenRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl UserTokenRepositoryTrait for UserTokenRepository {
+    // Issuing a new token invalidates the previous unused ones of the same purpose,
+    // so only the latest mail sent to the user works.
+    async fn create_token(
+        &self,
+        user_id: i64,
+        purpose: TokenPurpose,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> RepositoryResult<()> {
+        let mut tx = self.conn.begin().await?;
+
+        let _ = sqlx::query(
+            "UPDATE user_tokens SET used_at = NOW() WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .execute(&mut *tx)
+        .await?;
+
+        let _ = sqlx::query(
+            "INSERT INTO user_tokens (user_id, purpose, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn find_valid_token(
+        &self,
+        token_hash: &str,
+        purpose: TokenPurpose,
+    ) -> RepositoryResult<UserToken> {
+        let user_token = sqlx::query_as::<_, UserToken>(
+            r#"
+            SELECT * FROM user_tokens
+            WHERE token_hash = $1
+            AND purpose = $2
+            AND used_at IS NULL
+            AND expires_at > NOW()
+            "#,
+        )
+        .bind(token_hash)
+        .bind(purpose)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(user_token)
+    }
+
+    async fn find_latest_token(
+        &self,
+        user_id: i64,
+        purpose: TokenPurpose,
+    ) -> RepositoryResult<Option<UserToken>> {
+        let user_token = sqlx::query_as::<_, UserToken>(
+            r#"
+            SELECT * FROM user_tokens
+            WHERE user_id = $1
+            AND purpose = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .fetch_optional(&*self.conn)
+        .await?;
+        Ok(user_token)
+    }
+
+    async fn consume_token(&self, id: i64) -> RepositoryResult<bool> {
+        let affected_rows = sqlx::query(
+            "UPDATE user_tokens SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+        )
+        .bind(id)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+
+        Ok(affected_rows > 0)
+    }
+}
@@ -1,7 +1,7 @@
 use super::RepositoryResult;
-use crate::domain::{
-    dto::thread::ResponseThread,
-    model::{cursor_claims::CursorClaims, votes::ReactionType},
+use crate::domain::model::{
+    cursor_claims::CursorClaims,
+    votes::{ReactedThread, ReactionType},
 };
 use async_trait::async_trait;
 use sqlx::PgPool;
@@ -34,14 +34,14 @@ pub trait VotesRepositoryTrait: Send + Sync {
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> RepositoryResult<Vec<ResponseThread>>;
+    ) -> RepositoryResult<Vec<ReactedThread>>;
 
     async fn list_downvoted_thread(
         &self,
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> RepositoryResult<Vec<ResponseThread>>;
+    ) -> RepositoryResult<Vec<ReactedThread>>;
 }
 
 pub struct VotesRepository {
@@ -117,8 +117,8 @@ impl VotesRepositoryTrait for VotesRepository {
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> RepositoryResult<Vec<ResponseThread>> {
-        let upvoted_list = sqlx::query_as::<_, ResponseThread>(
+    ) -> RepositoryResult<Vec<ReactedThread>> {
+        let upvoted_list = sqlx::query_as::<_, ReactedThread>(
             r#"
             SELECT
                 t.*,
@@ -137,14 +137,15 @@ impl VotesRepositoryTrait for VotesRepository {
             LEFT JOIN views v 
                 ON v.thread_id = t.id
             WHERE t.is_deleted = FALSE
-            AND t.created_at < $2
             GROUP BY t.id
+            HAVING ($2::TIMESTAMPTZ IS NULL OR (MAX(u.created_at), t.id) < ($2, $3))
             ORDER BY reacted_at DESC, t.id DESC
-            LIMIT $3
+            LIMIT $4
             "#
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
@@ -157,8 +158,8 @@ impl VotesRepositoryTrait for VotesRepository {
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> RepositoryResult<Vec<ResponseThread>> {
-        let downvoted_list = sqlx::query_as::<_, ResponseThread>(
+    ) -> RepositoryResult<Vec<ReactedThread>> {
+        let downvoted_list = sqlx::query_as::<_, ReactedThread>(
             r#"
             SELECT
                 t.*,
@@ -177,14 +178,15 @@ impl VotesRepositoryTrait for VotesRepository {
             LEFT JOIN views v 
                 ON v.thread_id = t.id
             WHERE t.is_deleted = FALSE
-            AND t.created_at < $2
             GROUP BY t.id
+            HAVING ($2::TIMESTAMPTZ IS NULL OR (MAX(u.created_at), t.id) < ($2, $3))
             ORDER BY reacted_at DESC, t.id DESC
-            LIMIT $3
+            LIMIT $4
             "#
         )
         .bind(user_id)
-        .bind(cursor.created_at)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
         .bind(limit)
         .fetch_all(&*self.conn)
         .await?;
@@ -0,0 +1,327 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+use super::RepositoryResult;
+use crate::domain::model::{
+    cursor_claims::CursorClaims,
+    webhook::{DueWebhookDelivery, Webhook, WebhookDelivery},
+};
+
+#[async_trait]
+pub trait WebhookRepositoryTrait: Send + Sync {
+    async fn create_webhook(
+        &self,
+        user_id: i64,
+        url: &str,
+        secret: &str,
+        events: Vec<String>,
+        all_users: bool,
+    ) -> RepositoryResult<Webhook>;
+    async fn list_webhook(&self, user_id: i64) -> RepositoryResult<Vec<Webhook>>;
+    async fn find_webhook(&self, id: i64, user_id: i64) -> RepositoryResult<Webhook>;
+    async fn update_webhook(
+        &self,
+        id: i64,
+        user_id: i64,
+        url: Option<&str>,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+    ) -> RepositoryResult<Webhook>;
+    async fn delete_webhook(&self, id: i64, user_id: i64) -> RepositoryResult<bool>;
+    async fn enqueue_delivery(
+        &self,
+        subject_user_id: i64,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> RepositoryResult<u64>;
+    async fn list_delivery(
+        &self,
+        webhook_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<WebhookDelivery>>;
+    async fn redeliver(
+        &self,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> RepositoryResult<WebhookDelivery>;
+    async fn claim_due_delivery(
+        &self,
+        limit: i64,
+        lease_in_seconds: i64,
+    ) -> RepositoryResult<Vec<DueWebhookDelivery>>;
+    async fn complete_delivery(
+        &self,
+        id: i64,
+        response_status: i32,
+    ) -> RepositoryResult<()>;
+    async fn fail_delivery(
+        &self,
+        id: i64,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<()>;
+}
+
+pub struct WebhookRepository {
+    pub conn: Arc<PgPool>,
+}
+
+impl WebhookRepository {
+    pub fn new(conn: Arc<PgPool>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl WebhookRepositoryTrait for WebhookRepository {
+    async fn create_webhook(
+        &self,
+        user_id: i64,
+        url: &str,
+        secret: &str,
+        events: Vec<String>,
+        all_users: bool,
+    ) -> RepositoryResult<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            INSERT INTO webhooks (user_id, url, secret, events, all_users)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(url)
+        .bind(secret)
+        .bind(events)
+        .bind(all_users)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn list_webhook(&self, user_id: i64) -> RepositoryResult<Vec<Webhook>> {
+        let webhooks = sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at DESC, id DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(webhooks)
+    }
+
+    async fn find_webhook(&self, id: i64, user_id: i64) -> RepositoryResult<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            "SELECT * FROM webhooks WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn update_webhook(
+        &self,
+        id: i64,
+        user_id: i64,
+        url: Option<&str>,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+    ) -> RepositoryResult<Webhook> {
+        let webhook = sqlx::query_as::<_, Webhook>(
+            r#"
+            UPDATE webhooks
+            SET url = COALESCE($3, url),
+                events = COALESCE($4, events),
+                is_active = COALESCE($5, is_active),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(url)
+        .bind(events)
+        .bind(is_active)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(webhook)
+    }
+
+    async fn delete_webhook(&self, id: i64, user_id: i64) -> RepositoryResult<bool> {
+        let affected_rows =
+            sqlx::query("DELETE FROM webhooks WHERE id = $1 AND user_id = $2")
+                .bind(id)
+                .bind(user_id)
+                .execute(&*self.conn)
+                .await?
+                .rows_affected();
+        Ok(affected_rows > 0)
+    }
+
+    // One delivery for each active webhook of `subject_user_id`, or listening to
+    // every user, that subscribed to the event. Listening to every user needs the
+    // owner to still be an admin, not only when the webhook was created.
+    async fn enqueue_delivery(
+        &self,
+        subject_user_id: i64,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> RepositoryResult<u64> {
+        let affected_rows = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT w.id, $2, $3
+            FROM webhooks w
+            JOIN users u ON u.id = w.user_id
+            WHERE (w.user_id = $1 OR (w.all_users = TRUE AND u.role = 'ADMIN'))
+            AND w.is_active = TRUE
+            AND $2 = ANY(w.events)
+            "#,
+        )
+        .bind(subject_user_id)
+        .bind(event)
+        .bind(payload)
+        .execute(&*self.conn)
+        .await?
+        .rows_affected();
+        Ok(affected_rows)
+    }
+
+    async fn list_delivery(
+        &self,
+        webhook_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> RepositoryResult<Vec<WebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE webhook_id = $1
+            AND ($2::TIMESTAMPTZ IS NULL OR (created_at, id) < ($2, $3))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(cursor.sort_at)
+        .bind(cursor.id)
+        .bind(limit)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(deliveries)
+    }
+
+    // Queues the same payload again as a new delivery, the original stays in the log.
+    async fn redeliver(
+        &self,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> RepositoryResult<WebhookDelivery> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload, redelivery_of)
+            SELECT webhook_id, event, payload, id
+            FROM webhook_deliveries
+            WHERE id = $1 AND webhook_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(webhook_id)
+        .fetch_one(&*self.conn)
+        .await?;
+        Ok(delivery)
+    }
+
+    // Takes the due deliveries and pushes their next attempt past the lease, so
+    // another worker does not send them meanwhile. A worker that dies mid-send
+    // leaves them to be retried once the lease is over.
+    async fn claim_due_delivery(
+        &self,
+        limit: i64,
+        lease_in_seconds: i64,
+    ) -> RepositoryResult<Vec<DueWebhookDelivery>> {
+        let deliveries = sqlx::query_as::<_, DueWebhookDelivery>(
+            r#"
+            WITH due AS (
+                UPDATE webhook_deliveries d
+                SET attempts = d.attempts + 1,
+                    next_attempt_at = NOW() + make_interval(secs => $2),
+                    updated_at = NOW()
+                WHERE d.id IN (
+                    SELECT d2.id
+                    FROM webhook_deliveries d2
+                    JOIN webhooks w ON w.id = d2.webhook_id
+                    WHERE d2.status = 'PENDING'
+                    AND d2.next_attempt_at <= NOW()
+                    AND w.is_active = TRUE
+                    ORDER BY d2.next_attempt_at
+                    LIMIT $1
+                    FOR UPDATE OF d2 SKIP LOCKED
+                )
+                RETURNING d.id, d.webhook_id, d.event, d.payload, d.attempts
+            )
+            SELECT due.id, due.event, due.payload, due.attempts, w.url, w.secret
+            FROM due
+            JOIN webhooks w ON w.id = due.webhook_id
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_in_seconds as f64)
+        .fetch_all(&*self.conn)
+        .await?;
+        Ok(deliveries)
+    }
+
+    async fn complete_delivery(
+        &self,
+        id: i64,
+        response_status: i32,
+    ) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'SUCCEEDED', response_status = $2, last_error = NULL,
+                delivered_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+
+    // Retried at `retry_at`, or given up when it is `None`.
+    async fn fail_delivery(
+        &self,
+        id: i64,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> RepositoryResult<()> {
+        let _ = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'FAILED' ELSE 'PENDING' END::webhook_delivery_status_enum,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                response_status = $2, last_error = $3, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&*self.conn)
+        .await?;
+        Ok(())
+    }
+}
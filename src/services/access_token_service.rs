@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    domain::{
+        dto::user::{
+            RequestCreateAccessToken, ResponseAccessToken, ResponseCreatedAccessToken,
+        },
+        model::{
+            access_token::{PersonalAccessToken, ACCESS_TOKEN_PREFIX},
+            jwt_claims::JwtClaims,
+        },
+    },
+    error::CustomError,
+    repository::{
+        access_token_repo::AccessTokenRepositoryTrait, user_repo::UserRepositoryTrait,
+    },
+    utils::crypto,
+};
+
+const ACCESS_TOKEN_TOUCH_INTERVAL_IN_SECONDS: i64 = 60;
+const MAX_ACCESS_TOKEN_NAME_LENGTH: usize = 100;
+// `thr_pat_` and the first few characters, enough to tell tokens apart in a list
+const ACCESS_TOKEN_DISPLAY_PREFIX_LENGTH: usize = 12;
+const MAX_ACCESS_TOKEN_EXPIRATION_IN_DAYS: i64 = 365;
+
+pub struct AccessTokenService {
+    user_repo: Arc<dyn UserRepositoryTrait>,
+    access_token_repo: Arc<dyn AccessTokenRepositoryTrait>,
+}
+
+impl AccessTokenService {
+    pub fn new(
+        user_repo: Arc<dyn UserRepositoryTrait>,
+        access_token_repo: Arc<dyn AccessTokenRepositoryTrait>,
+    ) -> Self {
+        Self { user_repo, access_token_repo }
+    }
+
+    pub async fn create_access_token(
+        &self,
+        user_id: i64,
+        token_dto: RequestCreateAccessToken,
+    ) -> Result<ResponseCreatedAccessToken, CustomError> {
+        let name = token_dto.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_ACCESS_TOKEN_NAME_LENGTH {
+            return Err(CustomError::InvalidAccessTokenRequest(format!(
+                "Token name must be between 1 and {} characters",
+                MAX_ACCESS_TOKEN_NAME_LENGTH
+            )));
+        }
+        if token_dto.scopes.is_empty() {
+            return Err(CustomError::InvalidAccessTokenRequest(
+                "At least one scope is required".to_string(),
+            ));
+        }
+        let expires_at = match token_dto.expires_in_days {
+            Some(days) if !(1..=MAX_ACCESS_TOKEN_EXPIRATION_IN_DAYS).contains(&days) => {
+                return Err(CustomError::InvalidAccessTokenRequest(format!(
+                    "Token expiration must be between 1 and {} days",
+                    MAX_ACCESS_TOKEN_EXPIRATION_IN_DAYS
+                )))
+            }
+            Some(days) => Some(
+                Utc::now().checked_add_signed(Duration::days(days)).ok_or_else(|| {
+                    CustomError::InvalidAccessTokenRequest(
+                        "Token expiration is out of range".to_string(),
+                    )
+                })?,
+            ),
+            None => None,
+        };
+
+        let mut scopes: Vec<String> =
+            token_dto.scopes.iter().map(|scope| scope.as_str().to_string()).collect();
+        scopes.sort();
+        scopes.dedup();
+
+        let token = format!("{}{}", ACCESS_TOKEN_PREFIX, crypto::generate_token());
+        let access_token = self
+            .access_token_repo
+            .create_token(
+                user_id,
+                name,
+                &crypto::hash_token(&token),
+                &token[..ACCESS_TOKEN_DISPLAY_PREFIX_LENGTH],
+                scopes,
+                expires_at,
+            )
+            .await?;
+
+        Ok(ResponseCreatedAccessToken { token, access_token: to_response(access_token) })
+    }
+
+    pub async fn list_access_token(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<ResponseAccessToken>, CustomError> {
+        let access_token_list = self.access_token_repo.list_active_token(user_id).await?;
+        Ok(access_token_list.into_iter().map(to_response).collect())
+    }
+
+    pub async fn revoke_access_token(
+        &self,
+        user_id: i64,
+        token_id: i64,
+    ) -> Result<(), CustomError> {
+        if !self.access_token_repo.revoke_token(token_id, user_id).await? {
+            return Err(CustomError::NotFound);
+        }
+        Ok(())
+    }
+
+    // Resolves a `thr_pat_...` bearer token into the request context.
+    pub async fn verify_access_token(
+        &self,
+        token: &str,
+    ) -> Result<JwtClaims, CustomError> {
+        let access_token = match self
+            .access_token_repo
+            .find_active_token(&crypto::hash_token(token))
+            .await
+        {
+            Ok(access_token) => access_token,
+            Err(CustomError::NotFound) => {
+                return Err(CustomError::Unauthorized(
+                    "Invalid or expired token".to_string(),
+                ))
+            }
+            Err(err) => return Err(err),
+        };
+        let user = self.user_repo.find_user_by_id(access_token.user_id).await?;
+        if user.is_suspended {
+            return Err(CustomError::AccountSuspended);
+        }
+
+        // avoid writing on every single request
+        let is_stale = access_token.last_used_at.is_none_or(|last_used_at| {
+            Utc::now() - last_used_at
+                > Duration::seconds(ACCESS_TOKEN_TOUCH_INTERVAL_IN_SECONDS)
+        });
+        if is_stale {
+            self.access_token_repo.touch_token(access_token.id).await?;
+        }
+
+        Ok(JwtClaims::for_access_token(
+            user.id,
+            &user.email,
+            user.role,
+            access_token.scopes,
+        ))
+    }
+}
+
+fn to_response(access_token: PersonalAccessToken) -> ResponseAccessToken {
+    ResponseAccessToken {
+        id: access_token.id,
+        name: access_token.name,
+        token_prefix: access_token.token_prefix,
+        scopes: access_token.scopes,
+        last_used_at: access_token.last_used_at,
+        expires_at: access_token.expires_at,
+        created_at: access_token.created_at,
+    }
+}
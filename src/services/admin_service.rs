@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::{
+        dto::{
+            user::{RequestUpdateUserRole, ResponseAdminUser},
+            ResponsePage,
+        },
+        model::cursor_claims::{CursorClaims, CursorKind},
+    },
+    error::CustomError,
+    repository::{session_repo::SessionRepositoryTrait, user_repo::UserRepositoryTrait},
+    utils,
+};
+
+pub struct AdminService {
+    user_repo: Arc<dyn UserRepositoryTrait>,
+    session_repo: Arc<dyn SessionRepositoryTrait>,
+}
+
+impl AdminService {
+    pub fn new(
+        user_repo: Arc<dyn UserRepositoryTrait>,
+        session_repo: Arc<dyn SessionRepositoryTrait>,
+    ) -> Self {
+        Self { user_repo, session_repo }
+    }
+
+    pub async fn list_user(
+        &self,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ResponseAdminUser>, CustomError> {
+        // ordered by id only
+        let user_list = self.user_repo.list_user(cursor, limit + 1).await?;
+        let (user_list, next_cursor) =
+            utils::cursor::paginate(user_list, limit, |user| CursorClaims {
+                id: user.id,
+                ..CursorClaims::first_page(CursorKind::AdminUser)
+            });
+        Ok(ResponsePage::new(user_list, next_cursor))
+    }
+
+    // The role is carried in access tokens, so the user is signed out everywhere
+    // to make the new role apply immediately.
+    pub async fn update_user_role(
+        &self,
+        admin_id: i64,
+        user_id: i64,
+        role_dto: RequestUpdateUserRole,
+    ) -> Result<(), CustomError> {
+        check_not_self(admin_id, user_id)?;
+        self.user_repo.update_role(user_id, role_dto.role).await?;
+        self.session_repo.revoke_all_user_session(user_id, None).await?;
+        Ok(())
+    }
+
+    pub async fn suspend_user(
+        &self,
+        admin_id: i64,
+        user_id: i64,
+    ) -> Result<(), CustomError> {
+        check_not_self(admin_id, user_id)?;
+        self.user_repo.update_suspended(user_id, true).await?;
+        self.session_repo.revoke_all_user_session(user_id, None).await?;
+        Ok(())
+    }
+
+    pub async fn unsuspend_user(
+        &self,
+        admin_id: i64,
+        user_id: i64,
+    ) -> Result<(), CustomError> {
+        check_not_self(admin_id, user_id)?;
+        self.user_repo.update_suspended(user_id, false).await
+    }
+}
+
+// Keeps an admin from locking themselves out.
+fn check_not_self(admin_id: i64, user_id: i64) -> Result<(), CustomError> {
+    if admin_id == user_id {
+        return Err(CustomError::PermissionDenied(
+            "You cannot change your own account from the admin API".to_string(),
+        ));
+    }
+    Ok(())
+}
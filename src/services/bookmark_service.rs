@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::model::bookmark::BookmarkFolder,
+    error::CustomError,
+    repository::{
+        bookmark_repo::BookmarkRepositoryTrait, thread_repo::ThreadRepositoryTrait,
+    },
+};
+
+const MAX_BOOKMARK_FOLDERS: usize = 50;
+const MAX_BOOKMARK_FOLDER_NAME_LENGTH: usize = 50;
+
+pub struct BookmarkService {
+    thread_repo: Arc<dyn ThreadRepositoryTrait>,
+    bookmark_repo: Arc<dyn BookmarkRepositoryTrait>,
+}
+
+impl BookmarkService {
+    pub fn new(
+        thread_repo: Arc<dyn ThreadRepositoryTrait>,
+        bookmark_repo: Arc<dyn BookmarkRepositoryTrait>,
+    ) -> Self {
+        Self { thread_repo, bookmark_repo }
+    }
+
+    pub async fn bookmark_thread(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> Result<(), CustomError> {
+        let thread = self.thread_repo.get_thread_by_id(thread_id).await?;
+        if thread.is_deleted {
+            return Err(CustomError::NotFound);
+        }
+        if let Some(folder_id) = folder_id {
+            self.bookmark_repo.find_folder(folder_id, user_id).await?;
+        }
+        if !self.bookmark_repo.add_bookmark(user_id, thread_id, folder_id).await? {
+            return Err(CustomError::AlreadyBookmarked);
+        }
+        Ok(())
+    }
+
+    pub async fn unbookmark_thread(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+    ) -> Result<(), CustomError> {
+        if !self.bookmark_repo.remove_bookmark(user_id, thread_id).await? {
+            return Err(CustomError::NotBookmarked);
+        }
+        Ok(())
+    }
+
+    pub async fn move_bookmark(
+        &self,
+        user_id: i64,
+        thread_id: i64,
+        folder_id: Option<i64>,
+    ) -> Result<(), CustomError> {
+        if let Some(folder_id) = folder_id {
+            self.bookmark_repo.find_folder(folder_id, user_id).await?;
+        }
+        if !self.bookmark_repo.move_bookmark(user_id, thread_id, folder_id).await? {
+            return Err(CustomError::NotBookmarked);
+        }
+        Ok(())
+    }
+
+    // Makes sure the folder is the user's before listing it.
+    pub async fn check_folder(
+        &self,
+        user_id: i64,
+        folder_id: Option<i64>,
+    ) -> Result<(), CustomError> {
+        if let Some(folder_id) = folder_id {
+            self.bookmark_repo.find_folder(folder_id, user_id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn list_folder(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<BookmarkFolder>, CustomError> {
+        self.bookmark_repo.list_folder(user_id).await
+    }
+
+    pub async fn create_folder(
+        &self,
+        user_id: i64,
+        name: &str,
+    ) -> Result<BookmarkFolder, CustomError> {
+        let name = validate_folder_name(name)?;
+        let folders = self.bookmark_repo.list_folder(user_id).await?;
+        if folders.len() >= MAX_BOOKMARK_FOLDERS {
+            return Err(CustomError::InvalidBookmarkFolder(format!(
+                "You can have up to {} bookmark folders",
+                MAX_BOOKMARK_FOLDERS
+            )));
+        }
+        if has_folder_named(&folders, name, None) {
+            return Err(folder_name_taken());
+        }
+        self.bookmark_repo.create_folder(user_id, name).await
+    }
+
+    pub async fn rename_folder(
+        &self,
+        user_id: i64,
+        id: i64,
+        name: &str,
+    ) -> Result<BookmarkFolder, CustomError> {
+        let name = validate_folder_name(name)?;
+        let folders = self.bookmark_repo.list_folder(user_id).await?;
+        if has_folder_named(&folders, name, Some(id)) {
+            return Err(folder_name_taken());
+        }
+        self.bookmark_repo.rename_folder(id, user_id, name).await
+    }
+
+    // The bookmarks of the folder are kept, outside of any folder.
+    pub async fn delete_folder(&self, user_id: i64, id: i64) -> Result<(), CustomError> {
+        if !self.bookmark_repo.delete_folder(id, user_id).await? {
+            return Err(CustomError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+fn validate_folder_name(name: &str) -> Result<&str, CustomError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_BOOKMARK_FOLDER_NAME_LENGTH {
+        return Err(CustomError::InvalidBookmarkFolder(format!(
+            "Folder name must be between 1 and {} characters",
+            MAX_BOOKMARK_FOLDER_NAME_LENGTH
+        )));
+    }
+    Ok(name)
+}
+
+// Names are compared case-insensitively, like the unique index.
+fn has_folder_named(
+    folders: &[BookmarkFolder],
+    name: &str,
+    except_id: Option<i64>,
+) -> bool {
+    folders.iter().any(|folder| {
+        Some(folder.id) != except_id && folder.name.to_lowercase() == name.to_lowercase()
+    })
+}
+
+fn folder_name_taken() -> CustomError {
+    CustomError::InvalidBookmarkFolder(
+        "A folder with this name already exists".to_string(),
+    )
+}
@@ -1,22 +1,41 @@
 use std::sync::Arc;
 
+use serde_json::json;
+
 use crate::{
-    domain::model::{cursor_claims::CursorClaims, follow::FollowList, user::User},
+    domain::{
+        dto::ResponsePage,
+        model::{
+            cursor_claims::{CursorClaims, CursorKind},
+            follow::FollowList,
+            notification::NotificationType,
+            user::User,
+            webhook::WebhookEvent,
+        },
+    },
     error::CustomError,
     repository::{follow_repo::FollowRepositoryTrait, user_repo::UserRepositoryTrait},
+    services::{
+        notification_service::NotificationService, webhook_service::WebhookService,
+    },
+    utils,
 };
 
 pub struct FollowService {
     user_repo: Arc<dyn UserRepositoryTrait>,
     follow_repo: Arc<dyn FollowRepositoryTrait>,
+    notification_service: Arc<NotificationService>,
+    webhook_service: Arc<WebhookService>,
 }
 
 impl FollowService {
     pub fn new(
         user_repo: Arc<dyn UserRepositoryTrait>,
         follow_repo: Arc<dyn FollowRepositoryTrait>,
+        notification_service: Arc<NotificationService>,
+        webhook_service: Arc<WebhookService>,
     ) -> Self {
-        Self { user_repo, follow_repo }
+        Self { user_repo, follow_repo, notification_service, webhook_service }
     }
 
     pub async fn follow(
@@ -31,7 +50,18 @@ impl FollowService {
         if self.follow_repo.is_followed_user(user_id, target_user.id).await? {
             return Err(CustomError::AlreadyFollowed);
         }
-        self.follow_repo.follow_user(user_id, target_user.id).await
+        let followed = self.follow_repo.follow_user(user_id, target_user.id).await?;
+        self.notification_service
+            .notify(target_user.id, user_id, NotificationType::Follow, None)
+            .await;
+        self.webhook_service
+            .dispatch(
+                target_user.id,
+                WebhookEvent::UserFollowed,
+                json!({ "user_id": target_user.id, "follower_id": user_id }),
+            )
+            .await;
+        Ok(followed)
     }
 
     pub async fn unfollow(
@@ -53,13 +83,17 @@ impl FollowService {
         user_handle: &str,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<FollowList>, CustomError> {
+    ) -> Result<ResponsePage<FollowList>, CustomError> {
         let mut user = self.user_repo.find_user_by_handle(user_handle).await?;
         user = self.validate_user(user).await?;
 
         let follower_list =
-            self.follow_repo.list_follower(user.id, cursor, limit).await?;
-        Ok(follower_list)
+            self.follow_repo.list_follower(user.id, cursor, limit + 1).await?;
+        let (follower_list, next_cursor) =
+            utils::cursor::paginate(follower_list, limit, |follow| {
+                CursorClaims::new(CursorKind::Follower, follow.followed_at, follow.id)
+            });
+        Ok(ResponsePage::new(follower_list, next_cursor))
     }
 
     pub async fn list_user_following(
@@ -67,13 +101,17 @@ impl FollowService {
         user_handle: &str,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<FollowList>, CustomError> {
+    ) -> Result<ResponsePage<FollowList>, CustomError> {
         let mut user = self.user_repo.find_user_by_handle(user_handle).await?;
         user = self.validate_user(user).await?;
 
         let following_list =
-            self.follow_repo.list_following(user.id, cursor, limit).await?;
-        Ok(following_list)
+            self.follow_repo.list_following(user.id, cursor, limit + 1).await?;
+        let (following_list, next_cursor) =
+            utils::cursor::paginate(following_list, limit, |follow| {
+                CursorClaims::new(CursorKind::Following, follow.followed_at, follow.id)
+            });
+        Ok(ResponsePage::new(following_list, next_cursor))
     }
 
     async fn validate_follow(
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{error, warn};
+
+use crate::{
+    config,
+    domain::model::{login_attempt::LoginFailureReason, session::ClientInfo},
+    error::CustomError,
+    repository::{
+        login_attempt_repo::LoginAttemptRepositoryTrait,
+        login_throttle_repo::LoginThrottleRepositoryTrait,
+    },
+};
+
+// Caps the exponent so the backoff cannot overflow before hitting the max lockout.
+const MAX_BACKOFF_EXPONENT: i32 = 20;
+
+// Tracks failed signin attempts per account and per client IP.
+// Once a key reaches its threshold it is locked out with an exponential backoff.
+pub struct LoginGuardService {
+    login_throttle_repo: Arc<dyn LoginThrottleRepositoryTrait>,
+    login_attempt_repo: Arc<dyn LoginAttemptRepositoryTrait>,
+}
+
+impl LoginGuardService {
+    pub fn new(
+        login_throttle_repo: Arc<dyn LoginThrottleRepositoryTrait>,
+        login_attempt_repo: Arc<dyn LoginAttemptRepositoryTrait>,
+    ) -> Self {
+        Self { login_throttle_repo, login_attempt_repo }
+    }
+
+    pub async fn ensure_not_locked(
+        &self,
+        email: &str,
+        client_info: &ClientInfo,
+    ) -> Result<(), CustomError> {
+        let now = Utc::now();
+        let mut retry_after = 0;
+        for (throttle_key, _) in throttle_keys(email, client_info) {
+            let locked_until = self
+                .login_throttle_repo
+                .find_throttle(&throttle_key)
+                .await?
+                .and_then(|throttle| throttle.locked_until);
+            if let Some(locked_until) = locked_until.filter(|until| *until > now) {
+                retry_after = retry_after.max((locked_until - now).num_seconds() + 1);
+            }
+        }
+
+        if retry_after > 0 {
+            self.record_failed_attempt(
+                None,
+                email,
+                LoginFailureReason::LockedOut,
+                client_info,
+            )
+            .await;
+            return Err(CustomError::TooManyLoginAttempts(retry_after));
+        }
+        Ok(())
+    }
+
+    pub async fn register_failure(
+        &self,
+        user_id: Option<i64>,
+        email: &str,
+        reason: LoginFailureReason,
+        client_info: &ClientInfo,
+    ) -> Result<(), CustomError> {
+        self.record_failed_attempt(user_id, email, reason, client_info).await;
+
+        let envs = config::env::envs();
+        let window_start =
+            Utc::now() - Duration::seconds(envs.login_failure_window_in_seconds);
+        for (throttle_key, max_failures) in throttle_keys(email, client_info) {
+            let failure_count = self
+                .login_throttle_repo
+                .register_failure(&throttle_key, window_start)
+                .await?;
+            if let Some(lockout) = lockout_duration(failure_count, max_failures) {
+                warn!("Locking out '{}' for {} seconds", throttle_key, lockout);
+                self.login_throttle_repo
+                    .lock_until(&throttle_key, Utc::now() + Duration::seconds(lockout))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Only the account counter is cleared, one valid account must not reset
+    // the counter of an IP that is guessing passwords for others.
+    pub async fn register_success(&self, email: &str) -> Result<(), CustomError> {
+        self.login_throttle_repo.clear_throttle(&account_throttle_key(email)).await
+    }
+
+    // The audit trail must not block signin, failures are only logged.
+    async fn record_failed_attempt(
+        &self,
+        user_id: Option<i64>,
+        email: &str,
+        reason: LoginFailureReason,
+        client_info: &ClientInfo,
+    ) {
+        if let Err(err) = self
+            .login_attempt_repo
+            .record_failed_attempt(user_id, email, reason, client_info)
+            .await
+        {
+            error!("Failed to record login attempt: {:?}", err);
+        }
+    }
+}
+
+fn account_throttle_key(email: &str) -> String {
+    format!("account:{}", email.trim().to_lowercase())
+}
+
+fn throttle_keys(email: &str, client_info: &ClientInfo) -> Vec<(String, i64)> {
+    let envs = config::env::envs();
+    let mut throttle_keys =
+        vec![(account_throttle_key(email), envs.login_max_failures_per_account)];
+    if let Some(ip_address) = &client_info.ip_address {
+        throttle_keys
+            .push((format!("ip:{}", ip_address), envs.login_max_failures_per_ip));
+    }
+    throttle_keys
+}
+
+// base * 2^(failures - threshold), capped at the max lockout.
+fn lockout_duration(failure_count: i32, max_failures: i64) -> Option<i64> {
+    let exceeded = failure_count as i64 - max_failures;
+    if exceeded < 0 {
+        return None;
+    }
+
+    let envs = config::env::envs();
+    let exponent = exceeded.min(MAX_BACKOFF_EXPONENT as i64) as u32;
+    let lockout = envs.login_lockout_base_in_seconds.saturating_mul(1 << exponent);
+    Some(lockout.min(envs.login_lockout_max_in_seconds))
+}
@@ -1,4 +1,10 @@
+pub mod access_token_service;
+pub mod admin_service;
+pub mod bookmark_service;
 pub mod follow_service;
+pub mod login_guard_service;
+pub mod notification_service;
 pub mod thread_service;
 pub mod user_service;
 pub mod votes_service;
+pub mod webhook_service;
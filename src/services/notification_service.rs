@@ -0,0 +1,212 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::error;
+
+use crate::{
+    domain::{
+        dto::{
+            notification::{
+                NotificationPreferences, RequestNotificationParams, ResponseNotification,
+                ResponseUnreadNotificationCount,
+            },
+            thread::UserProfile,
+            ResponsePage,
+        },
+        model::{
+            cursor_claims::{CursorClaims, CursorKind},
+            notification::{Notification, NotificationType},
+        },
+    },
+    error::CustomError,
+    events::{Event, EventBus},
+    repository::notification_repo::NotificationRepositoryTrait,
+    utils,
+};
+
+// Actors shown with each notification, the count covers the rest.
+const ACTORS_PER_NOTIFICATION: i64 = 3;
+
+pub struct NotificationService {
+    notification_repo: Arc<dyn NotificationRepositoryTrait>,
+    event_bus: Arc<EventBus>,
+}
+
+impl NotificationService {
+    pub fn new(
+        notification_repo: Arc<dyn NotificationRepositoryTrait>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        Self { notification_repo, event_bus }
+    }
+
+    // Tells `user_id` that `actor_id` did something. Nobody is notified of their own
+    // actions or of a type they turned off. A failure is only logged, it must not
+    // fail the action that triggered it.
+    pub async fn notify(
+        &self,
+        user_id: i64,
+        actor_id: i64,
+        notification_type: NotificationType,
+        thread_id: Option<i64>,
+    ) {
+        if user_id == actor_id {
+            return;
+        }
+        if let Err(err) =
+            self.add_notification(user_id, actor_id, notification_type, thread_id).await
+        {
+            error!(
+                "Failed to notify user {} of {:?} by user {}: {:?}",
+                user_id, notification_type, actor_id, err
+            );
+        }
+    }
+
+    async fn add_notification(
+        &self,
+        user_id: i64,
+        actor_id: i64,
+        notification_type: NotificationType,
+        thread_id: Option<i64>,
+    ) -> Result<(), CustomError> {
+        let preferences = self.get_preferences(user_id).await?;
+        if preferences.get(notification_type) == Some(false) {
+            return Ok(());
+        }
+        self.notification_repo
+            .add_notification(user_id, notification_type, thread_id, actor_id)
+            .await?;
+        self.event_bus.publish(Event::Notification {
+            user_id,
+            notification_type,
+            thread_id,
+        });
+        Ok(())
+    }
+
+    pub async fn list_notification(
+        &self,
+        user_id: i64,
+        params: RequestNotificationParams,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ResponseNotification>, CustomError> {
+        let notifications = self
+            .notification_repo
+            .list_notification(
+                user_id,
+                params.unread_only.unwrap_or(false),
+                cursor,
+                limit + 1,
+            )
+            .await?;
+        let (notifications, next_cursor) =
+            utils::cursor::paginate(notifications, limit, |notification| {
+                CursorClaims::new(
+                    CursorKind::Notification,
+                    notification.updated_at,
+                    notification.id,
+                )
+            });
+
+        let notification_ids: Vec<i64> =
+            notifications.iter().map(|notification| notification.id).collect();
+        let mut actors: HashMap<i64, Vec<UserProfile>> = HashMap::new();
+        for actor in self
+            .notification_repo
+            .list_notification_actor(&notification_ids, ACTORS_PER_NOTIFICATION)
+            .await?
+        {
+            actors.entry(actor.notification_id).or_default().push(UserProfile {
+                id: actor.id,
+                handle: actor.handle.unwrap_or_default(),
+                profile_img: actor.profile_img_url.unwrap_or_default(),
+            });
+        }
+
+        let notifications = notifications
+            .into_iter()
+            .map(|notification| {
+                let actors = actors.remove(&notification.id).unwrap_or_default();
+                to_response(notification, actors)
+            })
+            .collect();
+        Ok(ResponsePage::new(notifications, next_cursor))
+    }
+
+    pub async fn count_unread_notification(
+        &self,
+        user_id: i64,
+    ) -> Result<ResponseUnreadNotificationCount, CustomError> {
+        let unread_count =
+            self.notification_repo.count_unread_notification(user_id).await?;
+        Ok(ResponseUnreadNotificationCount { unread_count })
+    }
+
+    pub async fn mark_read(&self, user_id: i64, id: i64) -> Result<(), CustomError> {
+        self.notification_repo.mark_read(user_id, id).await
+    }
+
+    pub async fn mark_all_read(&self, user_id: i64) -> Result<u64, CustomError> {
+        self.notification_repo.mark_all_read(user_id).await
+    }
+
+    // Every type is on unless turned off.
+    pub async fn get_preferences(
+        &self,
+        user_id: i64,
+    ) -> Result<NotificationPreferences, CustomError> {
+        let mut preferences = NotificationPreferences::default();
+        for notification_type in NotificationType::ALL {
+            preferences.set(notification_type, true);
+        }
+        for preference in self.notification_repo.list_preference(user_id).await? {
+            preferences.set(preference.notification_type, preference.enabled);
+        }
+        Ok(preferences)
+    }
+
+    pub async fn update_preferences(
+        &self,
+        user_id: i64,
+        new_preferences: NotificationPreferences,
+    ) -> Result<NotificationPreferences, CustomError> {
+        for notification_type in NotificationType::ALL {
+            if let Some(enabled) = new_preferences.get(notification_type) {
+                self.notification_repo
+                    .upsert_preference(user_id, notification_type, enabled)
+                    .await?;
+            }
+        }
+        self.get_preferences(user_id).await
+    }
+}
+
+fn to_response(
+    notification: Notification,
+    actors: Vec<UserProfile>,
+) -> ResponseNotification {
+    let subject = match (notification.actor_count, actors.first()) {
+        (1, Some(actor)) => actor.handle.clone(),
+        (1, None) => "Someone".to_string(),
+        (count, _) => format!("{} people", count),
+    };
+    let action = match notification.notification_type {
+        NotificationType::Follow => "followed you",
+        NotificationType::Reply => "replied to your thread",
+        NotificationType::Upvote => "upvoted your thread",
+        NotificationType::Mention => "mentioned you in a thread",
+    };
+    ResponseNotification {
+        id: notification.id,
+        notification_type: notification.notification_type,
+        thread_id: notification.thread_id,
+        message: format!("{} {}", subject, action),
+        actor_count: notification.actor_count,
+        actors,
+        is_read: notification.is_read,
+        read_at: notification.read_at,
+        created_at: notification.created_at,
+        updated_at: notification.updated_at,
+    }
+}
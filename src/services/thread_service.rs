@@ -1,44 +1,99 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use chrono::{Duration, DurationRound, Utc};
+use serde_json::json;
+use tracing::error;
 
 use crate::{
+    config,
     domain::{
+        dto::search::{
+            RequestSearchThreadParams, ResponseThreadSearchResult, ThreadSearchFilter,
+        },
         dto::thread::{
-            RequestCreateThread, RequestUpdateThread, ResponseThread,
+            MentionEntity, RequestCreateThread, RequestUpdateThread, ResponseThread,
+            ResponseThreadContext, ResponseThreadTree, ResponseThreadTreeNode,
             ResponseThreadWithUserProfile, UserProfile,
         },
-        model::cursor_claims::CursorClaims,
+        dto::ResponsePage,
+        model::{
+            cursor_claims::{CursorClaims, CursorKind},
+            notification::NotificationType,
+            thread::{ThreadMention, ThreadRevision, ThreadSnapshot, TrendingTag},
+            user::UserRole,
+            votes::ReactedThread,
+            webhook::WebhookEvent,
+        },
     },
     error::CustomError,
+    events::{Event, EventBus},
     repository::{
-        thread_repo::ThreadRepositoryTrait, user_repo::UserRepositoryTrait,
-        views_repo::ViewsRepositoryTrait, votes_repo::VotesRepositoryTrait,
+        bookmark_repo::BookmarkRepositoryTrait, thread_repo::ThreadRepositoryTrait,
+        user_repo::UserRepositoryTrait, views_repo::ViewsRepositoryTrait,
+        votes_repo::VotesRepositoryTrait,
+    },
+    services::{
+        notification_service::NotificationService, webhook_service::WebhookService,
     },
+    utils,
 };
 
+const DEFAULT_TREE_DEPTH: i32 = 3;
+const MAX_TREE_DEPTH: i32 = 10;
+const DEFAULT_TREE_PER_LEVEL: i64 = 5;
+const MAX_TREE_PER_LEVEL: i64 = 50;
+const MAX_SEARCH_QUERY_LENGTH: usize = 256;
+const DEFAULT_TRENDING_TAG_LIMIT: i64 = 10;
+const MAX_TRENDING_TAG_LIMIT: i64 = 50;
+// the top of the popularity ranking mixed into the feeds, every other thread
+const FEED_POPULAR_THREAD_COUNT: i64 = 50;
+
 pub struct ThreadService {
     user_repo: Arc<dyn UserRepositoryTrait>,
     thread_repo: Arc<dyn ThreadRepositoryTrait>,
     votes_repo: Arc<dyn VotesRepositoryTrait>,
     views_repo: Arc<dyn ViewsRepositoryTrait>,
+    bookmark_repo: Arc<dyn BookmarkRepositoryTrait>,
+    notification_service: Arc<NotificationService>,
+    webhook_service: Arc<WebhookService>,
+    event_bus: Arc<EventBus>,
 }
 
 // Implements cursor-based pagination.
 // The `cursor` parameter is used to fetch data starting from a specific point.
-// - It is a Base64-encoded string, e.g., `Base64.encode({"id":2, "created_at": "2025-02-15T06:52:51.576520123Z"})`.
-// - Decoding it will extract `{ id, created_at }`.
+// - It is an opaque, signed string returned as `next_cursor` by the previous page.
+// - Decoding it will extract the sort key of the last item, e.g. `{ sort_at, id }`.
 //
 // Function flow:
 // 1) Retrieve the user ID (`user_id`) using `user_repo.find_user_by_handle(user_handle)`.
-// 2) Decode the `cursor` value from Base64 to extract `{ id, created_at }`.
-// 3) Call `thread_repo.list_thread(cursor, limit)` to fetch threads starting from the cursor.
+// 2) Call `thread_repo.list_thread(cursor, limit + 1)`, the extra row tells whether
+//    there is a next page.
+// 3) Return the page with the cursor of its last item (`utils::cursor::paginate`).
 impl ThreadService {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_repo: Arc<dyn UserRepositoryTrait>,
         thread_repo: Arc<dyn ThreadRepositoryTrait>,
         votes_repo: Arc<dyn VotesRepositoryTrait>,
         views_repo: Arc<dyn ViewsRepositoryTrait>,
+        bookmark_repo: Arc<dyn BookmarkRepositoryTrait>,
+        notification_service: Arc<NotificationService>,
+        webhook_service: Arc<WebhookService>,
+        event_bus: Arc<EventBus>,
     ) -> Self {
-        Self { user_repo, thread_repo, votes_repo, views_repo }
+        Self {
+            user_repo,
+            thread_repo,
+            votes_repo,
+            views_repo,
+            bookmark_repo,
+            notification_service,
+            webhook_service,
+            event_bus,
+        }
     }
 
     pub async fn create_thread(
@@ -46,18 +101,68 @@ impl ThreadService {
         user_id: i64,
         thread: RequestCreateThread,
     ) -> Result<ResponseThread, CustomError> {
-        let thread_id = self.thread_repo.create_thread(user_id, thread).await?;
-        let thread = self.thread_repo.get_thread_by_id(thread_id).await?;
+        if config::env::envs().require_verified_email_to_post {
+            let user = self.user_repo.find_user_by_id(user_id).await?;
+            if !user.is_email_verified {
+                return Err(CustomError::EmailNotVerified);
+            }
+        }
+
+        let mentions = self.resolve_mentions(&thread.content).await?;
+        let thread_id =
+            self.thread_repo.create_thread(user_id, thread, &mentions).await?;
+        self.notify_mentions(thread_id, user_id, HashSet::new(), &mentions).await;
+        let thread = self
+            .with_mentions(self.thread_repo.get_thread_by_id(thread_id).await?)
+            .await?;
+        self.webhook_service
+            .dispatch(user_id, WebhookEvent::ThreadCreated, json!({ "thread": &thread }))
+            .await;
+
+        if let Some(parent_id) = thread.parent_thread {
+            self.event_bus.publish(Event::Reply {
+                parent_thread: parent_id,
+                thread_id: thread.id,
+                user_id,
+            });
+            match self.thread_repo.get_thread_by_id(parent_id).await {
+                Ok(parent) => {
+                    self.notification_service
+                        .notify(
+                            parent.user_id,
+                            user_id,
+                            NotificationType::Reply,
+                            Some(parent_id),
+                        )
+                        .await;
+                    self.webhook_service
+                        .dispatch(
+                            parent.user_id,
+                            WebhookEvent::ThreadReplied,
+                            json!({ "parent_thread": parent_id, "thread": &thread }),
+                        )
+                        .await
+                }
+                Err(err) => {
+                    error!("Failed to notify the reply to {}: {:?}", parent_id, err)
+                }
+            }
+        }
         Ok(thread)
     }
 
     pub async fn get_thread_by_id(
         &self,
         id: i64,
+        viewer_id: Option<i64>,
     ) -> Result<ResponseThreadWithUserProfile, CustomError> {
-        let _ = self.views_repo.view_thread(id).await?;
-        let thread = self.thread_repo.get_thread_by_id(id).await?;
-        let thread = self.enrich_thread_with_user_profile(thread).await?;
+        let mut thread = self.thread_repo.get_visible_thread_by_id(id).await?;
+        // a tombstone is not counted as viewed
+        if !thread.is_deleted {
+            self.views_repo.view_thread(id).await?;
+            thread.views += 1;
+        }
+        let thread = self.enrich_thread_with_user_profile(thread, viewer_id).await?;
         Ok(thread)
     }
 
@@ -66,13 +171,187 @@ impl ThreadService {
         parent_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<ResponseThreadWithUserProfile>, CustomError> {
-        let subthread = self
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        self.list_ranked_thread(Some(parent_id), cursor, limit, viewer_id).await
+    }
+
+    // Ancestors of a reply up to the root of the conversation, for breadcrumbs and
+    // "replying to @x". Deleted ancestors are returned as `[deleted]` tombstones.
+    pub async fn get_thread_context(
+        &self,
+        thread_id: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponseThreadContext, CustomError> {
+        let thread = self.thread_repo.get_visible_thread_by_id(thread_id).await?;
+        let thread = self.enrich_thread_with_user_profile(thread, viewer_id).await?;
+
+        let ancestors = self.thread_repo.list_ancestor(thread_id).await?;
+        let ancestors =
+            self.enrich_thread_list_with_user_profile(ancestors, viewer_id).await?;
+
+        let root = ancestors.first().unwrap_or(&thread).clone();
+        Ok(ResponseThreadContext { root, ancestors, thread })
+    }
+
+    // The replies of `thread_id` as a nested tree. A thread whose replies were cut by
+    // `per_level` carries a `replies_cursor`, one cut by `max_depth` only sets
+    // `has_more_replies`. The `cursor` pages the direct replies of `thread_id`.
+    pub async fn get_thread_tree(
+        &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        max_depth: Option<i32>,
+        per_level: Option<i64>,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponseThreadTree, CustomError> {
+        let max_depth = max_depth.unwrap_or(DEFAULT_TREE_DEPTH).clamp(1, MAX_TREE_DEPTH);
+        let per_level =
+            per_level.unwrap_or(DEFAULT_TREE_PER_LEVEL).clamp(1, MAX_TREE_PER_LEVEL);
+
+        let thread = self.thread_repo.get_visible_thread_by_id(thread_id).await?;
+        let ancestor_ids = self
             .thread_repo
-            .list_subthread_by_parent_id(parent_id, cursor, limit)
+            .list_ancestor(thread_id)
+            .await?
+            .into_iter()
+            .map(|ancestor| ancestor.id)
+            .collect();
+        let tree = self
+            .thread_repo
+            .list_reply_tree(thread_id, cursor, max_depth, per_level + 1)
+            .await?;
+
+        let depths: Vec<i32> = tree.iter().map(|row| row.depth).collect();
+        let reply_list = self
+            .enrich_thread_list_with_user_profile(
+                tree.into_iter().map(|row| row.thread).collect(),
+                viewer_id,
+            )
             .await?;
-        let subthread = self.enrich_thread_list_with_user_profile(subthread).await?;
-        Ok(subthread)
+
+        // replies grouped by parent, each group is in (created_at, id) order
+        let mut children: HashMap<i64, Vec<(i32, ResponseThreadWithUserProfile)>> =
+            HashMap::new();
+        for (depth, reply) in depths.into_iter().zip(reply_list) {
+            let parent_id = reply.parent_thread.unwrap_or_default();
+            children.entry(parent_id).or_default().push((depth, reply));
+        }
+        let (replies, next_cursor) =
+            build_reply_tree(thread_id, &mut children, max_depth, per_level);
+
+        let thread = self.enrich_thread_with_user_profile(thread, viewer_id).await?;
+        Ok(ResponseThreadTree {
+            ancestor_ids,
+            thread,
+            replies,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        })
+    }
+
+    pub async fn list_popular_thread(
+        &self,
+        cursor: CursorClaims,
+        limit: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        self.list_ranked_thread(None, cursor, limit, viewer_id).await
+    }
+
+    pub async fn search_thread(
+        &self,
+        params: RequestSearchThreadParams,
+        cursor: CursorClaims,
+        limit: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadSearchResult>, CustomError> {
+        let query = params.q.unwrap_or_default().trim().to_string();
+        if query.is_empty() {
+            return Err(CustomError::InvalidSearchQuery(
+                "The search query `q` is required".to_string(),
+            ));
+        }
+        if query.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+            return Err(CustomError::InvalidSearchQuery(format!(
+                "The search query must be at most {} characters",
+                MAX_SEARCH_QUERY_LENGTH
+            )));
+        }
+        if let (Some(from), Some(to)) = (params.from, params.to) {
+            if from >= to {
+                return Err(CustomError::InvalidSearchQuery(
+                    "`from` must be before `to`".to_string(),
+                ));
+            }
+        }
+
+        // handles are stored with their leading `@`
+        let author = params
+            .author
+            .map(|handle| handle.trim().trim_start_matches('@').to_string())
+            .filter(|handle| !handle.is_empty())
+            .map(|handle| format!("@{}", handle));
+        let filter = ThreadSearchFilter {
+            query,
+            author,
+            from: params.from,
+            to: params.to,
+            thread_type: params.thread_type,
+            min_votes: params.min_votes,
+        };
+
+        let thread_list =
+            self.thread_repo.search_thread(filter, cursor, limit + 1).await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |row| {
+                CursorClaims::by_rank(CursorKind::Search, row.rank, row.thread.id)
+            });
+
+        let mut results = Vec::new();
+        for row in thread_list {
+            results.push(ResponseThreadSearchResult {
+                thread: self
+                    .enrich_thread_with_user_profile(row.thread, viewer_id)
+                    .await?,
+                rank: row.rank,
+                title_highlight: row.title_highlight,
+                snippet: row.snippet,
+            });
+        }
+        Ok(ResponsePage::new(results, next_cursor))
+    }
+
+    pub async fn list_thread_by_tag(
+        &self,
+        tag: &str,
+        cursor: CursorClaims,
+        limit: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let tag = utils::text::normalize_tag(tag).ok_or(CustomError::InvalidTag)?;
+        let thread_list =
+            self.thread_repo.list_thread_by_tag(&tag, cursor, limit + 1).await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |thread| {
+                CursorClaims::new(CursorKind::Tag, thread.created_at, thread.id)
+            });
+        let enrich_thread_list =
+            self.enrich_thread_list_with_user_profile(thread_list, viewer_id).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
+    }
+
+    // Tags of the threads posted within the trending window, by decayed popularity.
+    pub async fn list_trending_tag(
+        &self,
+        limit: Option<i64>,
+    ) -> Result<Vec<TrendingTag>, CustomError> {
+        let window = config::env::envs().tag_trending_window_in_seconds;
+        let limit =
+            limit.unwrap_or(DEFAULT_TRENDING_TAG_LIMIT).clamp(1, MAX_TRENDING_TAG_LIMIT);
+        self.thread_repo
+            .list_trending_tag(Utc::now() - Duration::seconds(window), limit)
+            .await
     }
 
     pub async fn list_thread_by_user_handle(
@@ -80,16 +359,41 @@ impl ThreadService {
         user_handle: &str,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<ResponseThread>, CustomError> {
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThread>, CustomError> {
         let user = self.user_repo.find_user_by_handle(user_handle).await?;
         if !user.is_profile_complete {
             return Err(CustomError::ProfileNotCreated);
         }
 
         let thread_list =
-            self.thread_repo.list_thread_by_user_id(user.id, cursor, limit).await?;
-        // let enrich_thread_list = self.enrich_thread_list_with_user_profile(thread_list).await?;
-        Ok(thread_list)
+            self.thread_repo.list_thread_by_user_id(user.id, cursor, limit + 1).await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |thread| {
+                CursorClaims::new(CursorKind::UserThread, thread.created_at, thread.id)
+            });
+        let thread_list = self.with_mention_list(thread_list).await?;
+        let thread_list = self.with_bookmark_list(thread_list, viewer_id).await?;
+        Ok(ResponsePage::new(thread_list, next_cursor))
+    }
+
+    pub async fn list_mentioned_thread(
+        &self,
+        user_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let thread_list = self
+            .thread_repo
+            .list_thread_by_mentioned_user(user_id, cursor, limit + 1)
+            .await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |thread| {
+                CursorClaims::new(CursorKind::Mention, thread.created_at, thread.id)
+            });
+        let enrich_thread_list =
+            self.enrich_thread_list_with_user_profile(thread_list, Some(user_id)).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
     }
 
     pub async fn list_upvoted_thread(
@@ -97,17 +401,16 @@ impl ThreadService {
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<ResponseThreadWithUserProfile>, CustomError> {
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
         let user = self.user_repo.find_user_by_id(user_id).await?;
         if !user.is_profile_complete {
             return Err(CustomError::ProfileNotCreated);
         }
 
         let thread_list =
-            self.votes_repo.list_upvoted_thread(user.id, cursor, limit).await?;
-        let enrich_thread_list =
-            self.enrich_thread_list_with_user_profile(thread_list).await?;
-        Ok(enrich_thread_list)
+            self.votes_repo.list_upvoted_thread(user.id, cursor, limit + 1).await?;
+        self.paginate_reacted_thread(thread_list, limit, CursorKind::Upvoted, user.id)
+            .await
     }
 
     pub async fn list_downvoted_thread(
@@ -115,37 +418,126 @@ impl ThreadService {
         user_id: i64,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<ResponseThreadWithUserProfile>, CustomError> {
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
         let user = self.user_repo.find_user_by_id(user_id).await?;
         if !user.is_profile_complete {
             return Err(CustomError::ProfileNotCreated);
         }
 
         let thread_list =
-            self.votes_repo.list_downvoted_thread(user.id, cursor, limit).await?;
+            self.votes_repo.list_downvoted_thread(user.id, cursor, limit + 1).await?;
+        self.paginate_reacted_thread(thread_list, limit, CursorKind::Downvoted, user.id)
+            .await
+    }
+
+    pub async fn list_bookmarked_thread(
+        &self,
+        user_id: i64,
+        folder_id: Option<i64>,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let thread_list = self
+            .bookmark_repo
+            .list_bookmarked_thread(user_id, folder_id, cursor, limit + 1)
+            .await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |bookmarked| {
+                CursorClaims::new(
+                    CursorKind::Bookmark,
+                    bookmarked.bookmarked_at,
+                    bookmarked.thread.id,
+                )
+            });
+        let thread_list =
+            thread_list.into_iter().map(|bookmarked| bookmarked.thread).collect();
         let enrich_thread_list =
-            self.enrich_thread_list_with_user_profile(thread_list).await?;
-        Ok(enrich_thread_list)
+            self.enrich_thread_list_with_user_profile(thread_list, Some(user_id)).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
     }
 
+    // `user_id` personalizes the feed, `viewer_id` only marks the bookmarks.
+    // Every other thread of the feed comes from the top of the popularity snapshot,
+    // the others are the newest ones without those. Both parts are paged on their
+    // own keys (snapshot position, (created_at, id)), so the feed never repeats nor
+    // skips a thread while votes change.
     pub async fn list_recommend_thread(
         &self,
         user_id: Option<i64>,
         cursor: CursorClaims,
         limit: i64,
-    ) -> Result<Vec<ResponseThreadWithUserProfile>, CustomError> {
-        let mut thread_list = match user_id {
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let snapshot = self.find_ranking_snapshot(None, cursor.snapshot_id).await?;
+        // the popular part is frozen once ranked, whatever is ranked later
+        let snapshot =
+            self.rank_snapshot_until(snapshot, FEED_POPULAR_THREAD_COUNT).await?;
+        let popular_count = snapshot.entry_count.min(FEED_POPULAR_THREAD_COUNT);
+        let after_position = cursor.position.unwrap_or_default();
+
+        let popular_thread_ids =
+            self.thread_repo.list_snapshot_thread_id(snapshot.id, popular_count).await?;
+        let popular_thread_list = self
+            .thread_repo
+            .list_thread_by_snapshot(snapshot.id, after_position, limit + 1)
+            .await?
+            .into_iter()
+            .filter(|ranked| ranked.position <= popular_count);
+        let mut latest_thread_list = match user_id {
             Some(user_id) => {
-                self.list_personal_recommend_thread(user_id, cursor.clone(), limit)
-                    .await?
+                self.list_personal_recommend_thread(
+                    user_id,
+                    cursor.clone(),
+                    limit + 1,
+                    &popular_thread_ids,
+                )
+                .await?
+            }
+            None => {
+                self.list_guest_recommend_thread(
+                    cursor.clone(),
+                    limit + 1,
+                    &popular_thread_ids,
+                )
+                .await?
             }
-            None => self.list_guest_recommend_thread(cursor, limit).await?,
         };
-        thread_list.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        // the latest part is ordered by (created_at, id), a thread can come from
+        // several sources
+        latest_thread_list
+            .sort_by_key(|thread| std::cmp::Reverse((thread.created_at, thread.id)));
+        latest_thread_list.dedup_by_key(|thread| thread.id);
+
+        let mut popular_thread_list = popular_thread_list.peekable();
+        let mut latest_thread_list = latest_thread_list.into_iter().peekable();
+        let mut next_cursor =
+            CursorClaims { snapshot_id: Some(snapshot.id), ..cursor.clone() };
+        let mut thread_list = Vec::new();
+        while thread_list.len() < limit as usize {
+            let take_popular =
+                match (popular_thread_list.peek(), latest_thread_list.peek()) {
+                    (None, None) => break,
+                    (Some(_), Some(_)) => thread_list.len() % 2 == 0,
+                    (popular, _) => popular.is_some(),
+                };
+            if take_popular {
+                if let Some(ranked) = popular_thread_list.next() {
+                    next_cursor.position = Some(ranked.position);
+                    thread_list.push(ranked.thread);
+                }
+            } else if let Some(thread) = latest_thread_list.next() {
+                next_cursor.sort_at = Some(thread.created_at);
+                next_cursor.id = thread.id;
+                thread_list.push(thread);
+            }
+        }
+        let has_more =
+            popular_thread_list.peek().is_some() || latest_thread_list.peek().is_some();
+        let next_cursor = has_more.then(|| utils::cursor::encode_cursor(&next_cursor));
 
         let enrich_thread_list =
-            self.enrich_thread_list_with_user_profile(thread_list).await?;
-        Ok(enrich_thread_list.into_iter().take(limit as usize).collect())
+            self.enrich_thread_list_with_user_profile(thread_list, viewer_id).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
     }
 
     async fn list_personal_recommend_thread(
@@ -153,10 +545,20 @@ impl ThreadService {
         user_id: i64,
         cursor_claims: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> Result<Vec<ResponseThread>, CustomError> {
         let (common_threads, follower_threads) = tokio::join!(
-            self.list_guest_recommend_thread(cursor_claims.clone(), limit),
-            self.thread_repo.list_thread_by_following(user_id, cursor_claims, limit)
+            self.list_guest_recommend_thread(
+                cursor_claims.clone(),
+                limit,
+                excluded_thread_ids
+            ),
+            self.thread_repo.list_thread_by_following(
+                user_id,
+                cursor_claims,
+                limit,
+                excluded_thread_ids
+            )
         );
 
         // error handling
@@ -174,32 +576,103 @@ impl ThreadService {
         &self,
         cursor_claims: CursorClaims,
         limit: i64,
+        excluded_thread_ids: &[i64],
     ) -> Result<Vec<ResponseThread>, CustomError> {
-        let (popular_threads, recent_threads) = tokio::join!(
-            self.thread_repo
-                .list_thread_by_popularity_score(cursor_claims.clone(), limit),
-            self.thread_repo.list_thread_by_latest_created(cursor_claims, limit)
-        );
+        self.thread_repo
+            .list_thread_by_latest_created(cursor_claims, limit, excluded_thread_ids)
+            .await
+    }
 
-        // error handling
-        let popular_threads = popular_threads?;
-        let recent_threads = recent_threads?;
+    // Scores change between requests, so score ordered lists are paged through a
+    // snapshot of their order (see `find_ranking_snapshot`). Every page is read by
+    // rank, an item is never repeated nor skipped.
+    async fn list_ranked_thread(
+        &self,
+        parent_id: Option<i64>,
+        cursor: CursorClaims,
+        limit: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let kind = cursor.kind;
+        let snapshot = self.find_ranking_snapshot(parent_id, cursor.snapshot_id).await?;
+        let after_position = cursor.position.unwrap_or_default();
+        let snapshot =
+            self.rank_snapshot_until(snapshot, after_position + limit + 1).await?;
+
+        let thread_list = self
+            .thread_repo
+            .list_thread_by_snapshot(snapshot.id, after_position, limit + 1)
+            .await?;
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |ranked| {
+                CursorClaims::in_snapshot(kind, snapshot.id, ranked.position)
+            });
+        let thread_list = thread_list.into_iter().map(|ranked| ranked.thread).collect();
+        let enrich_thread_list =
+            self.enrich_thread_list_with_user_profile(thread_list, viewer_id).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
+    }
+
+    // The snapshot the cursor was issued with, or on the first page the one shared by
+    // every request of the current bucket (`THREAD_SNAPSHOT_BUCKET_IN_SECONDS`).
+    async fn find_ranking_snapshot(
+        &self,
+        parent_id: Option<i64>,
+        snapshot_id: Option<i64>,
+    ) -> Result<ThreadSnapshot, CustomError> {
+        let Some(snapshot_id) = snapshot_id else {
+            let envs = config::env::envs();
+            let bucket = Duration::seconds(envs.thread_snapshot_bucket_in_seconds.max(1));
+            let bucket_at = Utc::now()
+                .duration_trunc(bucket)
+                .map_err(|err| CustomError::InternalError(err.to_string()))?;
+            // a cursor stays valid for the TTL after the last request of the bucket
+            let expires_at = bucket_at
+                + bucket
+                + Duration::seconds(envs.thread_snapshot_ttl_in_seconds);
+            return self
+                .thread_repo
+                .find_or_create_snapshot(
+                    parent_id,
+                    bucket_at,
+                    envs.thread_snapshot_size.max(1),
+                    expires_at,
+                )
+                .await;
+        };
 
-        let mut common_thread_list = Vec::new();
-        common_thread_list.extend(popular_threads);
-        common_thread_list.extend(recent_threads);
+        let snapshot = match self.thread_repo.find_snapshot(snapshot_id).await {
+            Err(CustomError::NotFound) => return Err(CustomError::CursorExpired),
+            snapshot => snapshot?,
+        };
+        if snapshot.parent_thread != parent_id {
+            return Err(CustomError::InvalidCursor);
+        }
+        Ok(snapshot)
+    }
 
-        Ok(common_thread_list)
+    // Snapshots are ranked a chunk at a time (`THREAD_SNAPSHOT_SIZE`), the next chunk
+    // is ranked once paging reaches `count`.
+    async fn rank_snapshot_until(
+        &self,
+        mut snapshot: ThreadSnapshot,
+        count: i64,
+    ) -> Result<ThreadSnapshot, CustomError> {
+        let size = config::env::envs().thread_snapshot_size.max(1);
+        while !snapshot.is_complete && snapshot.entry_count < count {
+            snapshot = self.thread_repo.extend_snapshot(snapshot.id, size).await?;
+        }
+        Ok(snapshot)
     }
 
     async fn check_thread_permission(
         &self,
         user_id: i64,
         thread_id: i64,
-    ) -> Result<i64, CustomError> {
+    ) -> Result<ResponseThread, CustomError> {
         let thread = self.thread_repo.get_thread_by_id(thread_id).await?;
         if user_id == thread.user_id {
-            Ok(thread_id)
+            Ok(thread)
         } else {
             Err(CustomError::PermissionDenied(
                 "You do not have permission to modify or delete this thread.".to_owned(),
@@ -212,31 +685,286 @@ impl ThreadService {
         user_id: i64,
         thread_id: i64,
         thread_dto: RequestUpdateThread,
+        if_match: Option<i32>,
     ) -> Result<ResponseThread, CustomError> {
-        self.check_thread_permission(user_id, thread_id).await?;
-        self.thread_repo.update_thread(thread_id, thread_dto).await
+        if thread_dto.title.is_none() && thread_dto.content.is_none() {
+            return Err(CustomError::InvalidThreadUpdate(
+                "Nothing to update, send a title or a content".to_string(),
+            ));
+        }
+        if thread_dto.content.as_deref().is_some_and(|content| content.trim().is_empty())
+        {
+            return Err(CustomError::InvalidThreadUpdate(
+                "The content cannot be empty".to_string(),
+            ));
+        }
+
+        let thread = self.check_thread_permission(user_id, thread_id).await?;
+        let edit_window = config::env::envs().thread_edit_window_in_seconds;
+        if edit_window > 0
+            && Utc::now() - thread.created_at > Duration::seconds(edit_window)
+        {
+            return Err(CustomError::EditWindowExpired);
+        }
+
+        // `If-Match` fails with 412, a stale `version` in the body with 409
+        let version_mismatch = match (if_match, thread_dto.version) {
+            (Some(version), _) if version != thread.version => {
+                Some(CustomError::PreconditionFailed)
+            }
+            (_, Some(version)) if version != thread.version => {
+                Some(CustomError::VersionConflict)
+            }
+            _ => None,
+        };
+        if let Some(err) = version_mismatch {
+            return Err(err);
+        }
+
+        let (mentions, previous) = match &thread_dto.content {
+            Some(content) => (
+                self.resolve_mentions(content).await?,
+                self.mentioned_user_ids(thread_id).await?,
+            ),
+            None => (Vec::new(), HashSet::new()),
+        };
+
+        // the thread can still change between the read above and the update
+        let is_content_updated = thread_dto.content.is_some();
+        let thread = self
+            .thread_repo
+            .update_thread(thread_id, user_id, thread.version, thread_dto, &mentions)
+            .await?
+            .ok_or(if if_match.is_some() {
+                CustomError::PreconditionFailed
+            } else {
+                CustomError::VersionConflict
+            })?;
+        if is_content_updated {
+            self.notify_mentions(thread.id, user_id, previous, &mentions).await;
+        }
+        self.with_mentions(thread).await
     }
 
+    pub async fn list_thread_revision(
+        &self,
+        thread_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ThreadRevision>, CustomError> {
+        // revisions of a deleted thread are gone with it
+        self.thread_repo.get_thread_by_id(thread_id).await?;
+        let revisions =
+            self.thread_repo.list_revision(thread_id, cursor, limit + 1).await?;
+        let (revisions, next_cursor) =
+            utils::cursor::paginate(revisions, limit, |revision| CursorClaims {
+                id: revision.revision as i64,
+                ..CursorClaims::first_page(CursorKind::Revisions)
+            });
+        Ok(ResponsePage::new(revisions, next_cursor))
+    }
+
+    // Moderators can delete any thread, everyone else only their own.
     pub async fn delete_thread_by_id(
         &self,
         user_id: i64,
+        user_role: UserRole,
         thread_id: i64,
     ) -> Result<bool, CustomError> {
-        self.check_thread_permission(user_id, thread_id).await?;
-        self.thread_repo.delete_thread(thread_id).await
+        if user_role < UserRole::Moderator {
+            self.check_thread_permission(user_id, thread_id).await?;
+        }
+        self.thread_repo.delete_thread(thread_id, user_id).await
+    }
+
+    // Authors can restore what they deleted themselves, moderators anything, both
+    // only within the restore window.
+    pub async fn restore_thread_by_id(
+        &self,
+        user_id: i64,
+        user_role: UserRole,
+        thread_id: i64,
+    ) -> Result<ResponseThreadWithUserProfile, CustomError> {
+        let thread = self.thread_repo.find_deleted_thread(thread_id).await?;
+        if user_role < UserRole::Moderator && thread.deleted_by != Some(user_id) {
+            return Err(CustomError::PermissionDenied(
+                "You do not have permission to restore this thread.".to_owned(),
+            ));
+        }
+
+        let restore_window = config::env::envs().thread_restore_window_in_seconds;
+        let deleted_at = thread.deleted_at.unwrap_or(thread.updated_at);
+        if Utc::now() - deleted_at > Duration::seconds(restore_window) {
+            return Err(CustomError::RestoreWindowExpired);
+        }
+
+        self.thread_repo.restore_thread(thread_id).await?;
+        let thread = self.thread_repo.get_thread_by_id(thread_id).await?;
+        let thread = self.enrich_thread_with_user_profile(thread, Some(user_id)).await?;
+        Ok(thread)
+    }
+
+    // Hard-deletes the threads deleted longer than the retention period ago.
+    pub async fn purge_deleted_thread(&self) -> Result<u64, CustomError> {
+        let retention = config::env::envs().thread_retention_in_seconds;
+        let deleted_before = Utc::now() - Duration::seconds(retention);
+        self.thread_repo.purge_deleted_thread(deleted_before).await
+    }
+
+    // Resolves the `@handle`s of `content`, unknown, deleted and incomplete profiles
+    // are left as plain text.
+    async fn resolve_mentions(
+        &self,
+        content: &str,
+    ) -> Result<Vec<ThreadMention>, CustomError> {
+        let mut resolved: HashMap<String, Option<i64>> = HashMap::new();
+        let mut mentions = Vec::new();
+        for span in utils::text::parse_mentions(content) {
+            let user_id = match resolved.get(&span.handle) {
+                Some(user_id) => *user_id,
+                None => {
+                    let user_id =
+                        match self.user_repo.find_user_by_handle(&span.handle).await {
+                            Ok(user) if user.is_profile_complete => Some(user.id),
+                            Ok(_) | Err(CustomError::NotFound) => None,
+                            Err(err) => return Err(err),
+                        };
+                    resolved.insert(span.handle.clone(), user_id);
+                    user_id
+                }
+            };
+            if let Some(user_id) = user_id {
+                mentions.push(ThreadMention {
+                    user_id,
+                    start: span.start as i32,
+                    end: span.end as i32,
+                });
+            }
+        }
+        Ok(mentions)
+    }
+
+    async fn mentioned_user_ids(
+        &self,
+        thread_id: i64,
+    ) -> Result<HashSet<i64>, CustomError> {
+        let mentions = self.thread_repo.list_mention(&[thread_id]).await?;
+        Ok(mentions.into_iter().map(|row| row.mention.user_id).collect())
+    }
+
+    // Only the users not in `previous` are notified, once each.
+    async fn notify_mentions(
+        &self,
+        thread_id: i64,
+        author_id: i64,
+        previous: HashSet<i64>,
+        mentions: &[ThreadMention],
+    ) {
+        let mut notified: HashSet<i64> = HashSet::new();
+        for mention in mentions {
+            if previous.contains(&mention.user_id) || !notified.insert(mention.user_id) {
+                continue;
+            }
+            self.notification_service
+                .notify(
+                    mention.user_id,
+                    author_id,
+                    NotificationType::Mention,
+                    Some(thread_id),
+                )
+                .await;
+        }
+    }
+
+    async fn with_mentions(
+        &self,
+        thread: ResponseThread,
+    ) -> Result<ResponseThread, CustomError> {
+        let mut thread_list = self.with_mention_list(vec![thread]).await?;
+        Ok(thread_list.remove(0))
+    }
+
+    // Loads the mentions of a whole page in one query.
+    async fn with_mention_list(
+        &self,
+        mut thread_list: Vec<ResponseThread>,
+    ) -> Result<Vec<ResponseThread>, CustomError> {
+        let thread_ids: Vec<i64> = thread_list.iter().map(|thread| thread.id).collect();
+        let mut mentions: HashMap<i64, Vec<MentionEntity>> = HashMap::new();
+        for row in self.thread_repo.list_mention(&thread_ids).await? {
+            mentions.entry(row.thread_id).or_default().push(row.mention);
+        }
+        for thread in &mut thread_list {
+            thread.mentions = mentions.remove(&thread.id).unwrap_or_default();
+        }
+        Ok(thread_list)
+    }
+
+    // Whether the signed in viewer saved each thread of a page, loaded in one query.
+    // Always `false` for guests.
+    async fn with_bookmark_list(
+        &self,
+        mut thread_list: Vec<ResponseThread>,
+        viewer_id: Option<i64>,
+    ) -> Result<Vec<ResponseThread>, CustomError> {
+        let Some(viewer_id) = viewer_id else {
+            return Ok(thread_list);
+        };
+        let thread_ids: Vec<i64> = thread_list.iter().map(|thread| thread.id).collect();
+        let bookmarked: HashSet<i64> = self
+            .bookmark_repo
+            .list_bookmarked(viewer_id, &thread_ids)
+            .await?
+            .into_iter()
+            .collect();
+        for thread in &mut thread_list {
+            thread.is_bookmarked = bookmarked.contains(&thread.id);
+        }
+        Ok(thread_list)
     }
 
     async fn enrich_thread_with_user_profile(
         &self,
         thread: ResponseThread,
+        viewer_id: Option<i64>,
+    ) -> Result<ResponseThreadWithUserProfile, CustomError> {
+        let mut enrich_thread_list =
+            self.enrich_thread_list_with_user_profile(vec![thread], viewer_id).await?;
+        Ok(enrich_thread_list.remove(0))
+    }
+
+    // Expects the mentions and bookmarks to be loaded already, see `with_mention_list`
+    // and `with_bookmark_list`.
+    async fn enrich_loaded_thread(
+        &self,
+        thread: ResponseThread,
     ) -> Result<ResponseThreadWithUserProfile, CustomError> {
+        // a deleted thread still shown for its replies is rendered as a tombstone,
+        // the same shape everywhere: no title, author or mentions
+        if thread.is_deleted {
+            return Ok(ResponseThreadWithUserProfile {
+                title: None,
+                content: "[deleted]".to_string(),
+                mentions: Vec::new(),
+                is_bookmarked: false,
+                ..Self::thread_with_user_profile(thread, None)
+            });
+        }
+
         let user = self.user_repo.find_user_by_id(thread.user_id).await?;
         let user_profile = UserProfile {
             id: thread.user_id,
             handle: user.handle.unwrap_or_default(),
             profile_img: user.profile_img_url.unwrap_or_default(),
         };
-        Ok(ResponseThreadWithUserProfile {
+        Ok(Self::thread_with_user_profile(thread, Some(user_profile)))
+    }
+
+    fn thread_with_user_profile(
+        thread: ResponseThread,
+        user_profile: Option<UserProfile>,
+    ) -> ResponseThreadWithUserProfile {
+        ResponseThreadWithUserProfile {
             id: thread.id,
             title: thread.title,
             content: thread.content,
@@ -244,23 +972,397 @@ impl ThreadService {
             votes: thread.votes,
             views: thread.views,
             reply_count: thread.reply_count,
+            edited: thread.edited,
+            edit_count: thread.edit_count,
+            version: thread.version,
+            mentions: thread.mentions,
+            is_bookmarked: thread.is_bookmarked,
             is_deleted: thread.is_deleted,
             deleted_at: thread.deleted_at,
             created_at: thread.created_at,
             updated_at: thread.updated_at,
             user_profile,
-        })
+        }
+    }
+
+    async fn paginate_reacted_thread(
+        &self,
+        thread_list: Vec<ReactedThread>,
+        limit: i64,
+        kind: CursorKind,
+        user_id: i64,
+    ) -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError> {
+        let (thread_list, next_cursor) =
+            utils::cursor::paginate(thread_list, limit, |reacted| {
+                CursorClaims::new(kind, reacted.reacted_at, reacted.thread.id)
+            });
+        let thread_list = thread_list.into_iter().map(|reacted| reacted.thread).collect();
+        let enrich_thread_list =
+            self.enrich_thread_list_with_user_profile(thread_list, Some(user_id)).await?;
+        Ok(ResponsePage::new(enrich_thread_list, next_cursor))
     }
 
     async fn enrich_thread_list_with_user_profile(
         &self,
         thread_list: Vec<ResponseThread>,
+        viewer_id: Option<i64>,
     ) -> Result<Vec<ResponseThreadWithUserProfile>, CustomError> {
+        let thread_list = self.with_mention_list(thread_list).await?;
         let mut enrich_thread_list = Vec::new();
-        for thread in thread_list {
-            let enrich_thread = self.enrich_thread_with_user_profile(thread).await?;
+        for thread in self.with_bookmark_list(thread_list, viewer_id).await? {
+            let enrich_thread = self.enrich_loaded_thread(thread).await?;
             enrich_thread_list.push(enrich_thread)
         }
         Ok(enrich_thread_list)
     }
 }
+
+fn build_reply_tree(
+    parent_id: i64,
+    children: &mut HashMap<i64, Vec<(i32, ResponseThreadWithUserProfile)>>,
+    max_depth: i32,
+    per_level: i64,
+) -> (Vec<ResponseThreadTreeNode>, Option<String>) {
+    let replies = children.remove(&parent_id).unwrap_or_default();
+    let (replies, next_cursor) =
+        utils::cursor::paginate(replies, per_level, |(_, reply)| {
+            CursorClaims::new(CursorKind::Replies, reply.created_at, reply.id)
+        });
+
+    let mut nodes = Vec::new();
+    for (depth, reply) in replies {
+        let (nested_replies, replies_cursor) = if depth < max_depth {
+            build_reply_tree(reply.id, children, max_depth, per_level)
+        } else {
+            (Vec::new(), None)
+        };
+        let has_more_replies =
+            replies_cursor.is_some() || (depth >= max_depth && reply.reply_count > 0);
+        nodes.push(ResponseThreadTreeNode {
+            thread: reply,
+            depth,
+            replies: nested_replies,
+            has_more_replies,
+            replies_cursor,
+        });
+    }
+    (nodes, next_cursor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::thread_repo::ThreadRepository,
+        test_utils::{self, create_thread, create_user, vote},
+    };
+
+    // Reply `k` gets `k` up votes, or `replies.len() - 1 - k` once `reversed`.
+    async fn set_votes(
+        db_pool: &sqlx::PgPool,
+        voters: &[i64],
+        replies: &[i64],
+        reversed: bool,
+    ) {
+        for (k, reply) in replies.iter().enumerate() {
+            let count = if reversed { replies.len() - 1 - k } else { k };
+            for (v, voter) in voters.iter().enumerate() {
+                vote(db_pool, *voter, *reply, (v < count).then_some("UP")).await;
+            }
+        }
+    }
+
+    // Pages through `list` with the cursors it hands out, `between_pages` runs after
+    // every page.
+    async fn walk<F, L>(
+        kind: CursorKind,
+        limit: i64,
+        mut list: L,
+        mut between_pages: impl FnMut(usize) -> F,
+    ) -> Vec<i64>
+    where
+        F: std::future::Future<Output = ()>,
+        L: AsyncFnMut(
+            CursorClaims,
+        )
+            -> Result<ResponsePage<ResponseThreadWithUserProfile>, CustomError>,
+    {
+        let mut ids = Vec::new();
+        let mut cursor = None::<String>;
+        for page in 0.. {
+            let (claims, limit) =
+                utils::cursor::preprocessing_cursor(cursor.as_deref(), Some(limit), kind)
+                    .unwrap();
+            let result = list(claims).await.unwrap();
+            assert!(result.items.len() as i64 <= limit);
+            ids.extend(result.items.iter().map(|thread| thread.id));
+            between_pages(page).await;
+            match result.next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+        ids
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn subthread_pages_stay_stable_while_votes_change() {
+        let db_pool = test_utils::test_pool().await;
+        let state = test_utils::app_state(&db_pool);
+        let (author, _) = create_user(&db_pool).await;
+        let mut voters = Vec::new();
+        for _ in 0..6 {
+            voters.push(create_user(&db_pool).await.0);
+        }
+        let parent = create_thread(&db_pool, author, None).await;
+        let mut replies = Vec::new();
+        for _ in 0..7 {
+            replies.push(create_thread(&db_pool, author, Some(parent)).await);
+        }
+        set_votes(&db_pool, &voters, &replies, false).await;
+
+        // every page flips the order the votes give
+        let ids = walk(
+            CursorKind::Subthread,
+            2,
+            async |cursor| {
+                state
+                    .thread_service
+                    .list_subthread_by_parent_id(parent, cursor, 2, None)
+                    .await
+            },
+            |page| {
+                let (db_pool, voters, replies) = (&db_pool, &voters, &replies);
+                async move { set_votes(db_pool, voters, replies, page % 2 == 0).await }
+            },
+        )
+        .await;
+
+        let expected: Vec<i64> = replies.iter().rev().copied().collect();
+        assert_eq!(ids, expected);
+
+        voters.push(author);
+        test_utils::delete_users(&db_pool, &voters).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn snapshot_is_shared_by_the_bucket_and_ranked_a_chunk_at_a_time() {
+        let db_pool = test_utils::test_pool().await;
+        let thread_repo = Arc::new(ThreadRepository::new(Arc::new(db_pool.clone())));
+        let (author, _) = create_user(&db_pool).await;
+        let mut voters = Vec::new();
+        for _ in 0..6 {
+            voters.push(create_user(&db_pool).await.0);
+        }
+        let parent = create_thread(&db_pool, author, None).await;
+        let mut replies = Vec::new();
+        for _ in 0..7 {
+            replies.push(create_thread(&db_pool, author, Some(parent)).await);
+        }
+        set_votes(&db_pool, &voters, &replies, false).await;
+
+        let bucket_at = Utc::now().duration_trunc(Duration::seconds(60)).unwrap();
+        let expires_at = Utc::now() + Duration::minutes(5);
+        let snapshot = thread_repo
+            .find_or_create_snapshot(Some(parent), bucket_at, 3, expires_at)
+            .await
+            .unwrap();
+        assert_eq!((snapshot.entry_count, snapshot.is_complete), (3, false));
+
+        // the ranked chunks do not move, the next ones follow the current votes
+        set_votes(&db_pool, &voters, &replies, true).await;
+        let shared = thread_repo
+            .find_or_create_snapshot(Some(parent), bucket_at, 3, expires_at)
+            .await
+            .unwrap();
+        assert_eq!((shared.id, shared.entry_count), (snapshot.id, 3));
+        let snapshot = thread_repo.extend_snapshot(snapshot.id, 3).await.unwrap();
+        assert_eq!((snapshot.entry_count, snapshot.is_complete), (6, false));
+        let snapshot = thread_repo.extend_snapshot(snapshot.id, 3).await.unwrap();
+        assert_eq!((snapshot.entry_count, snapshot.is_complete), (7, true));
+        let snapshot = thread_repo.extend_snapshot(snapshot.id, 3).await.unwrap();
+        assert_eq!((snapshot.entry_count, snapshot.is_complete), (7, true));
+
+        let ranked =
+            thread_repo.list_thread_by_snapshot(snapshot.id, 0, 100).await.unwrap();
+        let positions: Vec<i64> = ranked.iter().map(|ranked| ranked.position).collect();
+        let ids: Vec<i64> = ranked.iter().map(|ranked| ranked.thread.id).collect();
+        assert_eq!(positions, (1..=7).collect::<Vec<i64>>());
+        assert_eq!(ids, [6, 5, 4, 0, 1, 2, 3].map(|k| replies[k]));
+
+        // concurrent first requests of a bucket share the snapshot
+        let next_bucket_at = bucket_at + Duration::minutes(1);
+        let mut requests = tokio::task::JoinSet::new();
+        for _ in 0..5 {
+            let thread_repo = Arc::clone(&thread_repo);
+            requests.spawn(async move {
+                thread_repo
+                    .find_or_create_snapshot(Some(parent), next_bucket_at, 3, expires_at)
+                    .await
+                    .unwrap()
+                    .id
+            });
+        }
+        let snapshot_ids: HashSet<i64> = requests.join_all().await.into_iter().collect();
+        assert_eq!(snapshot_ids.len(), 1);
+
+        voters.push(author);
+        test_utils::delete_users(&db_pool, &voters).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn feed_pages_stay_stable_while_votes_change() {
+        let db_pool = test_utils::test_pool().await;
+        let state = test_utils::app_state(&db_pool);
+        let (author, _) = create_user(&db_pool).await;
+        let mut voters = Vec::new();
+        for _ in 0..6 {
+            voters.push(create_user(&db_pool).await.0);
+        }
+        let mut threads = Vec::new();
+        for _ in 0..7 {
+            threads.push(create_thread(&db_pool, author, None).await);
+        }
+        set_votes(&db_pool, &voters, &threads, false).await;
+
+        for user_id in [None, Some(voters[0])] {
+            let ids = walk(
+                CursorKind::Feed,
+                3,
+                async |cursor| {
+                    state.thread_service.list_recommend_thread(user_id, cursor, 3, None).await
+                },
+                |page| {
+                    let (db_pool, voters, threads) = (&db_pool, &voters, &threads);
+                    async move { set_votes(db_pool, voters, threads, page % 2 == 0).await }
+                },
+            )
+            .await;
+            let unique_ids: HashSet<i64> = ids.iter().copied().collect();
+            assert_eq!(unique_ids.len(), ids.len(), "a thread is listed twice");
+            assert!(threads.iter().all(|thread| unique_ids.contains(thread)));
+        }
+
+        let ids = walk(
+            CursorKind::Popular,
+            3,
+            async |cursor| {
+                state.thread_service.list_popular_thread(cursor, 3, None).await
+            },
+            |page| {
+                let (db_pool, voters, threads) = (&db_pool, &voters, &threads);
+                async move { set_votes(db_pool, voters, threads, page % 2 == 0).await }
+            },
+        )
+        .await;
+        let unique_ids: HashSet<i64> = ids.iter().copied().collect();
+        assert_eq!(unique_ids.len(), ids.len(), "a thread is listed twice");
+
+        voters.push(author);
+        test_utils::delete_users(&db_pool, &voters).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn tree_keeps_deleted_replies_above_live_ones() {
+        let db_pool = test_utils::test_pool().await;
+        let state = test_utils::app_state(&db_pool);
+        let (author, _) = create_user(&db_pool).await;
+        let root = create_thread(&db_pool, author, None).await;
+        let deleted = create_thread(&db_pool, author, Some(root)).await;
+        let deleted_child = create_thread(&db_pool, author, Some(deleted)).await;
+        let live_grandchild = create_thread(&db_pool, author, Some(deleted_child)).await;
+        let deleted_leaf = create_thread(&db_pool, author, Some(root)).await;
+        let mut live = Vec::new();
+        for _ in 0..3 {
+            live.push(create_thread(&db_pool, author, Some(root)).await);
+        }
+        sqlx::query(
+            "UPDATE thread SET is_deleted = TRUE, deleted_at = NOW() WHERE id = ANY($1)",
+        )
+        .bind([deleted, deleted_child, deleted_leaf])
+        .execute(&db_pool)
+        .await
+        .unwrap();
+
+        let (cursor, _) =
+            utils::cursor::preprocessing_cursor(None, None, CursorKind::Replies).unwrap();
+        let tree = state
+            .thread_service
+            .get_thread_tree(root, cursor, Some(3), Some(2), None)
+            .await
+            .unwrap();
+
+        // the deleted leaf is dropped, the per-level limit counts the tombstone
+        let ids: Vec<i64> = tree.replies.iter().map(|node| node.thread.id).collect();
+        assert_eq!(ids, [deleted, live[0]]);
+        assert!(tree.has_more);
+        let tombstone = &tree.replies[0];
+        assert_eq!(tombstone.thread.content, "[deleted]");
+        assert_eq!(tombstone.thread.reply_count, 1);
+        assert_eq!(tombstone.replies[0].thread.id, deleted_child);
+        assert_eq!(tombstone.replies[0].replies[0].thread.id, live_grandchild);
+
+        // at `max_depth` the tombstone still reports the replies under it
+        let (cursor, _) =
+            utils::cursor::preprocessing_cursor(None, None, CursorKind::Replies).unwrap();
+        let tree = state
+            .thread_service
+            .get_thread_tree(root, cursor, Some(1), Some(2), None)
+            .await
+            .unwrap();
+        assert!(tree.replies[0].replies.is_empty());
+        assert!(tree.replies[0].has_more_replies);
+
+        test_utils::delete_users(&db_pool, &[author]).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn search_highlights_escape_the_thread_markup() {
+        let db_pool = test_utils::test_pool().await;
+        let state = test_utils::app_state(&db_pool);
+        let (author, handle) = create_user(&db_pool).await;
+        // the random handle is a word no other thread contains
+        let word = handle.trim_start_matches('@').to_string();
+        sqlx::query("INSERT INTO thread (user_id, title, content) VALUES ($1, $2, $3)")
+            .bind(author)
+            .bind(format!("<b>{}</b>", word))
+            .bind(format!("{} & <img src=x onerror=alert(1)>", word))
+            .execute(&db_pool)
+            .await
+            .unwrap();
+
+        let params = RequestSearchThreadParams {
+            q: Some(word.clone()),
+            author: None,
+            from: None,
+            to: None,
+            thread_type: None,
+            min_votes: None,
+            cursor: None,
+            limit: None,
+        };
+        let (cursor, limit) =
+            utils::cursor::preprocessing_cursor(None, None, CursorKind::Search).unwrap();
+        let page = state
+            .thread_service
+            .search_thread(params, cursor, limit, None)
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(
+            page.items[0].title_highlight.as_deref(),
+            Some(format!("&lt;b&gt;<mark>{}</mark>&lt;/b&gt;", word).as_str())
+        );
+        let snippet = &page.items[0].snippet;
+        assert!(snippet.starts_with(&format!("<mark>{}</mark> &amp; &lt;img", word)));
+        let unmarked = snippet.replace("<mark>", "").replace("</mark>", "");
+        assert!(!unmarked.contains('<') && !unmarked.contains('>'));
+
+        test_utils::delete_users(&db_pool, &[author]).await;
+    }
+}
@@ -1,55 +1,530 @@
 use std::sync::Arc;
 
+use chrono::{Duration, Utc};
+use tracing::{error, warn};
+
 use crate::{
+    config,
     domain::{
+        dto::search::{RequestSearchUserParams, ResponseUserSearchResult},
         dto::user::{
-            RequestSignin, RequestSignup, RequestUpsertProfile, ResponseProfile,
-            ResponseSignin,
+            RequestChangePassword, RequestDisableTwoFactor, RequestForgotPassword,
+            RequestRefreshToken, RequestResetPassword, RequestSignin, RequestSignup,
+            RequestTwoFactorCode, RequestTwoFactorSignin, RequestUpsertProfile,
+            RequestVerifyEmail, ResponseProfile, ResponseRecoveryCodes, ResponseSession,
+            ResponseSignin, ResponseSigninResult, ResponseTwoFactorChallenge,
+            ResponseTwoFactorEnrollment,
+        },
+        model::{
+            jwt_claims::JwtClaims,
+            login_attempt::LoginFailureReason,
+            session::{ClientInfo, RefreshToken},
+            two_factor::RecoveryCode,
+            two_factor_claims::TwoFactorChallengeClaims,
+            user::User,
+            user_token::TokenPurpose,
         },
-        model::{jwt_claims::JwtClaims, user::User},
     },
     error::CustomError,
-    repository::{follow_repo::FollowRepositoryTrait, user_repo::UserRepositoryTrait},
-    utils::crypto,
+    mailer::{Mail, Mailer},
+    repository::{
+        follow_repo::FollowRepositoryTrait, session_repo::SessionRepositoryTrait,
+        two_factor_repo::TwoFactorRepositoryTrait, user_repo::UserRepositoryTrait,
+        user_token_repo::UserTokenRepositoryTrait,
+    },
+    services::login_guard_service::LoginGuardService,
+    utils::{crypto, totp},
 };
 
+const SESSION_TOUCH_INTERVAL_IN_SECONDS: i64 = 60;
+const RECOVERY_CODE_COUNT: usize = 10;
+const DEFAULT_USER_SEARCH_LIMIT: i64 = 10;
+const MAX_USER_SEARCH_LIMIT: i64 = 20;
+const MAX_USER_SEARCH_QUERY_LENGTH: usize = 64;
+
 pub struct UserService {
     user_repo: Arc<dyn UserRepositoryTrait>,
     follow_repo: Arc<dyn FollowRepositoryTrait>,
+    session_repo: Arc<dyn SessionRepositoryTrait>,
+    user_token_repo: Arc<dyn UserTokenRepositoryTrait>,
+    two_factor_repo: Arc<dyn TwoFactorRepositoryTrait>,
+    login_guard: Arc<LoginGuardService>,
+    mailer: Arc<dyn Mailer>,
 }
 
 impl UserService {
     pub fn new(
         user_repo: Arc<dyn UserRepositoryTrait>,
         follow_repo: Arc<dyn FollowRepositoryTrait>,
+        session_repo: Arc<dyn SessionRepositoryTrait>,
+        user_token_repo: Arc<dyn UserTokenRepositoryTrait>,
+        two_factor_repo: Arc<dyn TwoFactorRepositoryTrait>,
+        login_guard: Arc<LoginGuardService>,
+        mailer: Arc<dyn Mailer>,
     ) -> Self {
-        Self { user_repo, follow_repo }
+        Self {
+            user_repo,
+            follow_repo,
+            session_repo,
+            user_token_repo,
+            two_factor_repo,
+            login_guard,
+            mailer,
+        }
     }
 
     pub async fn signup(&self, user: RequestSignup) -> Result<String, CustomError> {
+        if !is_valid_email(&user.email) {
+            return Err(CustomError::InvalidEmail);
+        }
         if user.password != user.password_confirm {
             return Err(CustomError::PasswordMismatch);
         }
 
-        match self.user_repo.create_user(user).await {
-            Ok(msg) => Ok(msg),
-            Err(msg) => Err(msg),
+        let hashed_password = hash_password(user.password).await?;
+        let new_user = self.user_repo.create_user(&user.email, &hashed_password).await?;
+        if let Err(err) = self.send_verification_mail(&new_user).await {
+            error!("Failed to send verification mail: {:?}", err);
+        }
+        Ok("User created successfully. Please check your email to verify it.".to_string())
+    }
+
+    pub async fn verify_email(
+        &self,
+        verify_dto: RequestVerifyEmail,
+    ) -> Result<(), CustomError> {
+        let token_hash = crypto::hash_token(&verify_dto.token);
+        let user_token = match self
+            .user_token_repo
+            .find_valid_token(&token_hash, TokenPurpose::EmailVerification)
+            .await
+        {
+            Ok(user_token) => user_token,
+            Err(CustomError::NotFound) => return Err(CustomError::InvalidToken),
+            Err(err) => return Err(err),
+        };
+        if !self.user_token_repo.consume_token(user_token.id).await? {
+            return Err(CustomError::InvalidToken);
+        }
+        self.user_repo.mark_email_verified(user_token.user_id).await
+    }
+
+    pub async fn resend_verification_email(
+        &self,
+        user_id: i64,
+    ) -> Result<(), CustomError> {
+        let user = self.user_repo.find_user_by_id(user_id).await?;
+        if user.is_email_verified {
+            return Err(CustomError::EmailAlreadyVerified);
+        }
+
+        let latest_token = self
+            .user_token_repo
+            .find_latest_token(user.id, TokenPurpose::EmailVerification)
+            .await?;
+        if let Some(latest_token) = latest_token {
+            let resend_available_at = latest_token.created_at
+                + Duration::seconds(
+                    config::env::envs().email_verification_resend_interval_in_seconds,
+                );
+            let now = Utc::now();
+            if resend_available_at > now {
+                let retry_after = (resend_available_at - now).num_seconds() + 1;
+                return Err(CustomError::VerificationMailThrottled(retry_after));
+            }
         }
+
+        self.send_verification_mail(&user).await
     }
 
     pub async fn signin(
         &self,
         user: RequestSignin,
+        client_info: ClientInfo,
+    ) -> Result<ResponseSigninResult, CustomError> {
+        self.login_guard.ensure_not_locked(&user.email, &client_info).await?;
+
+        let user_from_db = match self.user_repo.find_user_by_email(&user.email).await {
+            Ok(user_from_db) => user_from_db,
+            Err(CustomError::NotFound) => {
+                self.login_guard
+                    .register_failure(
+                        None,
+                        &user.email,
+                        LoginFailureReason::UnknownEmail,
+                        &client_info,
+                    )
+                    .await?;
+                verify_dummy_password(user.password).await?;
+                return Err(CustomError::InvalidCredentials);
+            }
+            Err(err) => return Err(err),
+        };
+        if let Err(err) =
+            verify_password(user.password.clone(), user_from_db.hash_password.clone())
+                .await
+        {
+            if matches!(err, CustomError::InvalidCredentials) {
+                self.login_guard
+                    .register_failure(
+                        Some(user_from_db.id),
+                        &user.email,
+                        LoginFailureReason::InvalidPassword,
+                        &client_info,
+                    )
+                    .await?;
+            }
+            return Err(err);
+        }
+        if user_from_db.is_suspended {
+            return Err(CustomError::AccountSuspended);
+        }
+        // the plain password is only available here, upgrade outdated hashes
+        if crypto::password_needs_rehash(&user_from_db.hash_password) {
+            self.rehash_password(user_from_db.id, &user.password).await;
+        }
+
+        // the password alone is not enough, hand out a challenge for the second step
+        if user_from_db.is_totp_enabled {
+            let challenge_claims = TwoFactorChallengeClaims::new(user_from_db.id);
+            let challenge_token = TwoFactorChallengeClaims::encode_jwt(challenge_claims)?;
+            return Ok(ResponseSigninResult::TwoFactorRequired(
+                ResponseTwoFactorChallenge { two_factor_required: true, challenge_token },
+            ));
+        }
+
+        self.login_guard.register_success(&user_from_db.email).await?;
+        let tokens = self.create_session_tokens(&user_from_db, client_info).await?;
+        Ok(ResponseSigninResult::Authenticated(tokens))
+    }
+
+    pub async fn signin_two_factor(
+        &self,
+        signin_dto: RequestTwoFactorSignin,
+        client_info: ClientInfo,
     ) -> Result<ResponseSignin, CustomError> {
-        let user_from_db = self.user_repo.find_user_by_email(&user.email).await?;
-        match crypto::verify_password(&user.password, &user_from_db.hash_password) {
-            Ok(_) => {
-                let token_claims = JwtClaims::new(user_from_db.id, &user_from_db.email);
-                let token = JwtClaims::encode_jwt(token_claims)?;
-                return Ok(ResponseSignin { token });
+        let challenge_claims =
+            TwoFactorChallengeClaims::decode_jwt(&signin_dto.challenge_token)
+                .ok_or(CustomError::InvalidToken)?;
+        let user = self.user_repo.find_user_by_id(challenge_claims.id).await?;
+        if !user.is_totp_enabled {
+            return Err(CustomError::InvalidToken);
+        }
+        if user.is_suspended {
+            return Err(CustomError::AccountSuspended);
+        }
+        // the challenge token is valid for a while, so guess the code is throttled too
+        self.login_guard.ensure_not_locked(&user.email, &client_info).await?;
+
+        if let Err(err) = self
+            .verify_second_factor(
+                &user,
+                signin_dto.code.as_deref(),
+                signin_dto.recovery_code.as_deref(),
+            )
+            .await
+        {
+            if matches!(err, CustomError::InvalidTwoFactorCode) {
+                self.login_guard
+                    .register_failure(
+                        Some(user.id),
+                        &user.email,
+                        LoginFailureReason::InvalidTwoFactor,
+                        &client_info,
+                    )
+                    .await?;
             }
+            return Err(err);
+        }
+
+        self.login_guard.register_success(&user.email).await?;
+        self.create_session_tokens(&user, client_info).await
+    }
+
+    // Stores a new secret that only takes effect after `confirm_two_factor`.
+    pub async fn enroll_two_factor(
+        &self,
+        user_id: i64,
+    ) -> Result<ResponseTwoFactorEnrollment, CustomError> {
+        let user = self.user_repo.find_user_by_id(user_id).await?;
+        if user.is_totp_enabled {
+            return Err(CustomError::TwoFactorAlreadyEnabled);
+        }
+
+        let secret = totp::generate_secret();
+        self.two_factor_repo.set_pending_secret(user.id, &secret).await?;
+
+        let provisioning_uri = totp::provisioning_uri(
+            &secret,
+            &user.email,
+            &config::env::envs().totp_issuer,
+        );
+        Ok(ResponseTwoFactorEnrollment { secret, provisioning_uri })
+    }
+
+    // Recovery codes are only shown once, right here.
+    pub async fn confirm_two_factor(
+        &self,
+        user_id: i64,
+        code_dto: RequestTwoFactorCode,
+    ) -> Result<ResponseRecoveryCodes, CustomError> {
+        let user = self.user_repo.find_user_by_id(user_id).await?;
+        if user.is_totp_enabled {
+            return Err(CustomError::TwoFactorAlreadyEnabled);
+        }
+        let secret =
+            user.totp_secret.as_deref().ok_or(CustomError::TwoFactorNotEnabled)?;
+        let used_step = totp::verify_code(
+            secret,
+            &code_dto.code,
+            Utc::now().timestamp() as u64,
+            None,
+        )
+        .ok_or(CustomError::InvalidTwoFactorCode)?;
+
+        let recovery_codes: Vec<String> =
+            (0..RECOVERY_CODE_COUNT).map(|_| totp::generate_recovery_code()).collect();
+        let recovery_code_hashes = hash_recovery_codes(recovery_codes.clone()).await?;
+        self.two_factor_repo
+            .enable_totp(user.id, used_step, recovery_code_hashes)
+            .await?;
+
+        Ok(ResponseRecoveryCodes { recovery_codes })
+    }
+
+    pub async fn disable_two_factor(
+        &self,
+        user_id: i64,
+        disable_dto: RequestDisableTwoFactor,
+    ) -> Result<(), CustomError> {
+        let user = self.user_repo.find_user_by_id(user_id).await?;
+        if !user.is_totp_enabled {
+            return Err(CustomError::TwoFactorNotEnabled);
+        }
+        verify_password(disable_dto.password, user.hash_password.clone()).await?;
+        self.verify_second_factor(
+            &user,
+            disable_dto.code.as_deref(),
+            disable_dto.recovery_code.as_deref(),
+        )
+        .await?;
+
+        self.two_factor_repo.disable_totp(user.id).await
+    }
+
+    // Refresh token rotation.
+    // Every refresh token can be exchanged exactly once. Presenting an already used
+    // token means it leaked (or was replayed), so the whole session is revoked and
+    // both the attacker and the legitimate client have to sign in again.
+    pub async fn refresh(
+        &self,
+        refresh_dto: RequestRefreshToken,
+    ) -> Result<ResponseSignin, CustomError> {
+        let stored_token = self.find_refresh_token(&refresh_dto.refresh_token).await?;
+        let session =
+            self.session_repo.find_session_by_id(stored_token.session_id).await?;
+        if session.is_revoked {
+            return Err(CustomError::InvalidRefreshToken);
+        }
+        if stored_token.is_used {
+            self.revoke_reused_session(session.id).await?;
+            return Err(CustomError::InvalidRefreshToken);
+        }
+        if stored_token.expires_at < Utc::now() {
+            return Err(CustomError::InvalidRefreshToken);
+        }
+
+        let user = self.user_repo.find_user_by_id(session.user_id).await?;
+        let refresh_token = crypto::generate_token();
+        let rotated = self
+            .session_repo
+            .rotate_refresh_token(
+                stored_token.id,
+                session.id,
+                &crypto::hash_token(&refresh_token),
+                refresh_token_expires_at(),
+            )
+            .await?;
+        if !rotated {
+            // lost the race against another request presenting the same token
+            self.revoke_reused_session(session.id).await?;
+            return Err(CustomError::InvalidRefreshToken);
+        }
+
+        let token_claims = JwtClaims::new(user.id, &user.email, user.role, session.id);
+        let token = JwtClaims::encode_jwt(token_claims)?;
+        Ok(ResponseSignin { token, refresh_token })
+    }
+
+    pub async fn signout(
+        &self,
+        refresh_dto: RequestRefreshToken,
+    ) -> Result<(), CustomError> {
+        let stored_token = self.find_refresh_token(&refresh_dto.refresh_token).await?;
+        self.session_repo.revoke_session(stored_token.session_id).await?;
+        Ok(())
+    }
+
+    // Changing the password signs out every other device, the current session stays.
+    pub async fn change_password(
+        &self,
+        user_id: i64,
+        session_id: Option<i64>,
+        password_dto: RequestChangePassword,
+    ) -> Result<(), CustomError> {
+        if password_dto.new_password != password_dto.new_password_confirm {
+            return Err(CustomError::PasswordMismatch);
+        }
+
+        let user = self.user_repo.find_user_by_id(user_id).await?;
+        verify_password(password_dto.current_password, user.hash_password.clone())
+            .await?;
+
+        let hashed_password = hash_password(password_dto.new_password).await?;
+        self.user_repo.update_password(user.id, &hashed_password).await?;
+        self.session_repo.revoke_all_user_session(user.id, session_id).await?;
+        Ok(())
+    }
+
+    // Always succeeds so that the endpoint cannot be used to find registered emails.
+    pub async fn forgot_password(
+        &self,
+        forgot_dto: RequestForgotPassword,
+    ) -> Result<(), CustomError> {
+        let user = match self.user_repo.find_user_by_email(&forgot_dto.email).await {
+            Ok(user) => user,
+            Err(CustomError::NotFound) => return Ok(()),
             Err(err) => return Err(err),
+        };
+
+        let reset_token = crypto::generate_token();
+        let expires_at = Utc::now()
+            + Duration::seconds(config::env::envs().password_reset_expiration_in_seconds);
+        self.user_token_repo
+            .create_token(
+                user.id,
+                TokenPurpose::PasswordReset,
+                &crypto::hash_token(&reset_token),
+                expires_at,
+            )
+            .await?;
+
+        let reset_url = format!(
+            "{}/reset-password?token={}",
+            config::env::envs().app_base_url,
+            reset_token
+        );
+        let mail = Mail {
+            to: user.email,
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Someone requested a password reset for your account.\n\
+                 Open the link below to choose a new password. It expires at {}.\n\n{}\n\n\
+                 If it wasn't you, you can ignore this mail.",
+                expires_at.to_rfc3339(),
+                reset_url
+            ),
+        };
+        if let Err(err) = self.mailer.send(mail).await {
+            error!("Failed to send password reset mail: {:?}", err);
+        }
+        Ok(())
+    }
+
+    // Resetting the password signs out every device.
+    pub async fn reset_password(
+        &self,
+        reset_dto: RequestResetPassword,
+    ) -> Result<(), CustomError> {
+        if reset_dto.new_password != reset_dto.new_password_confirm {
+            return Err(CustomError::PasswordMismatch);
+        }
+
+        let token_hash = crypto::hash_token(&reset_dto.token);
+        let user_token = match self
+            .user_token_repo
+            .find_valid_token(&token_hash, TokenPurpose::PasswordReset)
+            .await
+        {
+            Ok(user_token) => user_token,
+            Err(CustomError::NotFound) => return Err(CustomError::InvalidToken),
+            Err(err) => return Err(err),
+        };
+        if !self.user_token_repo.consume_token(user_token.id).await? {
+            return Err(CustomError::InvalidToken);
+        }
+
+        let hashed_password = hash_password(reset_dto.new_password).await?;
+        self.user_repo.update_password(user_token.user_id, &hashed_password).await?;
+        self.session_repo.revoke_all_user_session(user_token.user_id, None).await?;
+        Ok(())
+    }
+
+    // Called by `mw_require_auth` on every request so that revoked sessions
+    // lose access immediately instead of when the access token expires.
+    pub async fn verify_session(
+        &self,
+        token_context: &JwtClaims,
+    ) -> Result<(), CustomError> {
+        let session_id = token_context
+            .sid
+            .ok_or(CustomError::Unauthorized("Session not found".to_string()))?;
+        let session = match self.session_repo.find_session_by_id(session_id).await {
+            Ok(session) => session,
+            Err(CustomError::NotFound) => {
+                return Err(CustomError::Unauthorized("Session not found".to_string()))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let now = Utc::now();
+        if session.user_id != token_context.id
+            || session.is_revoked
+            || session.expires_at < now
+        {
+            return Err(CustomError::Unauthorized(
+                "Session has been revoked or expired".to_string(),
+            ));
+        }
+
+        // avoid writing on every single request
+        if now - session.last_used_at
+            > Duration::seconds(SESSION_TOUCH_INTERVAL_IN_SECONDS)
+        {
+            self.session_repo.touch_session(session.id).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn list_session(
+        &self,
+        user_id: i64,
+        current_session_id: Option<i64>,
+    ) -> Result<Vec<ResponseSession>, CustomError> {
+        let session_list = self.session_repo.list_active_session(user_id).await?;
+        Ok(session_list
+            .into_iter()
+            .map(|session| ResponseSession {
+                id: session.id,
+                user_agent: session.user_agent,
+                ip_address: session.ip_address,
+                is_current: Some(session.id) == current_session_id,
+                created_at: session.created_at,
+                last_used_at: session.last_used_at,
+                expires_at: session.expires_at,
+            })
+            .collect())
+    }
+
+    pub async fn revoke_session(
+        &self,
+        user_id: i64,
+        session_id: i64,
+    ) -> Result<(), CustomError> {
+        if !self.session_repo.revoke_user_session(session_id, user_id).await? {
+            return Err(CustomError::NotFound);
         }
+        Ok(())
     }
 
     pub async fn me(&self, user_id: i64) -> Result<ResponseProfile, CustomError> {
@@ -66,6 +541,8 @@ impl UserService {
             handle: user.handle.unwrap_or_default(),
             profile_img_url: user.profile_img_url.unwrap_or_default(),
             bio: user.bio,
+            is_email_verified: user.is_email_verified,
+            role: user.role,
             created_at: user.created_at,
             updated_at: user.updated_at,
             follower_count,
@@ -89,6 +566,8 @@ impl UserService {
             name: profile.name.unwrap_or_default(),
             handle: profile.handle.unwrap_or_default(),
             bio: profile.bio,
+            is_email_verified: profile.is_email_verified,
+            role: profile.role,
             profile_img_url: profile.profile_img_url.unwrap_or_default(),
             created_at: profile.created_at,
             updated_at: profile.updated_at,
@@ -114,6 +593,8 @@ impl UserService {
             handle: user.handle.unwrap_or_default(),
             profile_img_url: user.profile_img_url.unwrap_or_default(),
             bio: user.bio,
+            is_email_verified: user.is_email_verified,
+            role: user.role,
             created_at: user.created_at,
             updated_at: user.updated_at,
             follower_count,
@@ -121,6 +602,161 @@ impl UserService {
         })
     }
 
+    // Handle and name autocomplete, `searcher_id` is `None` for guests.
+    pub async fn search_user(
+        &self,
+        searcher_id: Option<i64>,
+        params: RequestSearchUserParams,
+    ) -> Result<Vec<ResponseUserSearchResult>, CustomError> {
+        let query = params.q.unwrap_or_default();
+        let query = query.trim().trim_start_matches('@').to_lowercase();
+        if query.is_empty() {
+            return Err(CustomError::InvalidSearchQuery(
+                "The search query `q` is required".to_string(),
+            ));
+        }
+        if query.chars().count() > MAX_USER_SEARCH_QUERY_LENGTH {
+            return Err(CustomError::InvalidSearchQuery(format!(
+                "The search query must be at most {} characters",
+                MAX_USER_SEARCH_QUERY_LENGTH
+            )));
+        }
+
+        // the query is matched with LIKE, its wildcards are literal
+        let query = query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+        let limit = params
+            .limit
+            .unwrap_or(DEFAULT_USER_SEARCH_LIMIT)
+            .clamp(1, MAX_USER_SEARCH_LIMIT);
+        self.user_repo.search_user(&query, searcher_id, limit).await
+    }
+
+    async fn find_refresh_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<RefreshToken, CustomError> {
+        let token_hash = crypto::hash_token(refresh_token);
+        match self.session_repo.find_refresh_token(&token_hash).await {
+            Ok(stored_token) => Ok(stored_token),
+            Err(CustomError::NotFound) => Err(CustomError::InvalidRefreshToken),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn create_session_tokens(
+        &self,
+        user: &User,
+        client_info: ClientInfo,
+    ) -> Result<ResponseSignin, CustomError> {
+        let refresh_token = crypto::generate_token();
+        let session_id = self
+            .session_repo
+            .create_session(
+                user.id,
+                &crypto::hash_token(&refresh_token),
+                refresh_token_expires_at(),
+                client_info,
+            )
+            .await?;
+
+        let token_claims = JwtClaims::new(user.id, &user.email, user.role, session_id);
+        let token = JwtClaims::encode_jwt(token_claims)?;
+        Ok(ResponseSignin { token, refresh_token })
+    }
+
+    // Failing to upgrade the hash must not fail the signin, the old hash still works.
+    async fn rehash_password(&self, user_id: i64, password: &str) {
+        let result = match hash_password(password.to_string()).await {
+            Ok(hashed_password) => {
+                self.user_repo.update_password(user_id, &hashed_password).await
+            }
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            warn!("Failed to upgrade password hash: {:?}", err);
+        }
+    }
+
+    async fn verify_second_factor(
+        &self,
+        user: &User,
+        code: Option<&str>,
+        recovery_code: Option<&str>,
+    ) -> Result<(), CustomError> {
+        match (code, recovery_code) {
+            (Some(code), _) => {
+                let secret = user
+                    .totp_secret
+                    .as_deref()
+                    .ok_or(CustomError::TwoFactorNotEnabled)?;
+                let used_step = totp::verify_code(
+                    secret,
+                    code,
+                    Utc::now().timestamp() as u64,
+                    user.totp_last_used_step,
+                )
+                .ok_or(CustomError::InvalidTwoFactorCode)?;
+                if !self.two_factor_repo.update_last_used_step(user.id, used_step).await?
+                {
+                    return Err(CustomError::InvalidTwoFactorCode);
+                }
+                Ok(())
+            }
+            (None, Some(recovery_code)) => {
+                let recovery_code = totp::normalize_recovery_code(recovery_code);
+                let stored_codes =
+                    self.two_factor_repo.list_unused_recovery_code(user.id).await?;
+                let code_id = find_recovery_code(recovery_code, stored_codes)
+                    .await?
+                    .ok_or(CustomError::InvalidTwoFactorCode)?;
+                if !self.two_factor_repo.consume_recovery_code(code_id).await? {
+                    return Err(CustomError::InvalidTwoFactorCode);
+                }
+                Ok(())
+            }
+            (None, None) => Err(CustomError::InvalidTwoFactorCode),
+        }
+    }
+
+    async fn send_verification_mail(&self, user: &User) -> Result<(), CustomError> {
+        let verification_token = crypto::generate_token();
+        let expires_at = Utc::now()
+            + Duration::seconds(
+                config::env::envs().email_verification_expiration_in_seconds,
+            );
+        self.user_token_repo
+            .create_token(
+                user.id,
+                TokenPurpose::EmailVerification,
+                &crypto::hash_token(&verification_token),
+                expires_at,
+            )
+            .await?;
+
+        let verify_url = format!(
+            "{}/verify-email?token={}",
+            config::env::envs().app_base_url,
+            verification_token
+        );
+        let mail = Mail {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Welcome! Open the link below to verify your email address. \
+                 It expires at {}.\n\n{}",
+                expires_at.to_rfc3339(),
+                verify_url
+            ),
+        };
+        self.mailer.send(mail).await
+    }
+
+    async fn revoke_reused_session(&self, session_id: i64) -> Result<(), CustomError> {
+        warn!("Refresh token reuse detected, revoking session {}", session_id);
+        self.session_repo.revoke_session(session_id).await?;
+        Ok(())
+    }
+
     async fn validate_user(&self, user: User) -> Result<User, CustomError> {
         if !user.is_profile_complete {
             return Err(CustomError::ProfileNotCreated);
@@ -128,3 +764,81 @@ impl UserService {
         Ok(user)
     }
 }
+
+fn refresh_token_expires_at() -> chrono::DateTime<Utc> {
+    Utc::now()
+        + Duration::seconds(config::env::envs().refresh_token_expiration_in_seconds)
+}
+
+// Only rejects obviously malformed addresses, the verification mail does the rest.
+fn is_valid_email(email: &str) -> bool {
+    if email.len() > 254 || email.chars().any(char::is_whitespace) {
+        return false;
+    }
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
+
+// Password hashes are slow on purpose, so every hash and verification runs here,
+// off the async workers.
+async fn run_blocking<T, F>(f: F) -> Result<T, CustomError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| CustomError::InternalError(err.to_string()))
+}
+
+async fn hash_password(password: String) -> Result<String, CustomError> {
+    run_blocking(move || crypto::hash_password(&password)).await?
+}
+
+async fn verify_password(
+    password: String,
+    hashed_password: String,
+) -> Result<bool, CustomError> {
+    run_blocking(move || crypto::verify_password(&password, &hashed_password)).await?
+}
+
+async fn verify_dummy_password(password: String) -> Result<(), CustomError> {
+    run_blocking(move || crypto::verify_dummy_password(&password)).await
+}
+
+// Hashed like passwords.
+async fn hash_recovery_codes(
+    recovery_codes: Vec<String>,
+) -> Result<Vec<String>, CustomError> {
+    run_blocking(move || {
+        recovery_codes.iter().map(|code| crypto::hash_password(code)).collect()
+    })
+    .await?
+}
+
+// Id of the stored code `recovery_code` matches.
+async fn find_recovery_code(
+    recovery_code: String,
+    stored_codes: Vec<RecoveryCode>,
+) -> Result<Option<i64>, CustomError> {
+    run_blocking(move || {
+        stored_codes
+            .iter()
+            .find(|stored_code| {
+                matches!(
+                    crypto::verify_password(&recovery_code, &stored_code.code_hash),
+                    Ok(true)
+                )
+            })
+            .map(|stored_code| stored_code.id)
+    })
+    .await
+}
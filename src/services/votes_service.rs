@@ -1,18 +1,34 @@
 use std::sync::Arc;
 
+use serde_json::json;
+use tracing::error;
+
 use crate::{
-    domain::model::votes::ReactionType,
+    config,
+    domain::{
+        dto::thread::ResponseThread,
+        model::{
+            notification::NotificationType, votes::ReactionType, webhook::WebhookEvent,
+        },
+    },
     error::CustomError,
+    events::{Event, EventBus},
     repository::{
         thread_repo::ThreadRepositoryTrait, user_repo::UserRepositoryTrait,
         votes_repo::VotesRepositoryTrait,
     },
+    services::{
+        notification_service::NotificationService, webhook_service::WebhookService,
+    },
 };
 
 pub struct VotesService {
     user_repo: Arc<dyn UserRepositoryTrait>,
     thread_repo: Arc<dyn ThreadRepositoryTrait>,
     votes_repo: Arc<dyn VotesRepositoryTrait>,
+    notification_service: Arc<NotificationService>,
+    webhook_service: Arc<WebhookService>,
+    event_bus: Arc<EventBus>,
 }
 
 impl VotesService {
@@ -20,8 +36,18 @@ impl VotesService {
         user_repo: Arc<dyn UserRepositoryTrait>,
         thread_repo: Arc<dyn ThreadRepositoryTrait>,
         votes_repo: Arc<dyn VotesRepositoryTrait>,
+        notification_service: Arc<NotificationService>,
+        webhook_service: Arc<WebhookService>,
+        event_bus: Arc<EventBus>,
     ) -> Self {
-        Self { user_repo, thread_repo, votes_repo }
+        Self {
+            user_repo,
+            thread_repo,
+            votes_repo,
+            notification_service,
+            webhook_service,
+            event_bus,
+        }
     }
 
     pub async fn react(
@@ -30,11 +56,32 @@ impl VotesService {
         target_thread_id: i64,
         reaction: ReactionType,
     ) -> Result<(), CustomError> {
-        let _ = self.validate_react(user_id, target_thread_id).await?;
+        let thread = self.validate_react(user_id, target_thread_id).await?;
         if self.votes_repo.is_reacted_thread(user_id, target_thread_id).await? {
             return Err(CustomError::AlreadyReacted);
         }
-        self.votes_repo.react_thread(user_id, target_thread_id, reaction).await
+        // down votes are not notified
+        let is_upvote = reaction == ReactionType::Up;
+        self.votes_repo.react_thread(user_id, target_thread_id, reaction.clone()).await?;
+        self.publish_votes(target_thread_id).await;
+        self.webhook_service
+            .dispatch(
+                thread.user_id,
+                WebhookEvent::ThreadVoted,
+                json!({ "thread_id": thread.id, "user_id": user_id, "reaction": reaction }),
+            )
+            .await;
+        if is_upvote {
+            self.notification_service
+                .notify(
+                    thread.user_id,
+                    user_id,
+                    NotificationType::Upvote,
+                    Some(thread.id),
+                )
+                .await;
+        }
+        Ok(())
     }
 
     pub async fn react_cancel(
@@ -43,18 +90,30 @@ impl VotesService {
         target_thread_id: i64,
         reaction: ReactionType,
     ) -> Result<(), CustomError> {
-        let _ = self.validate_react(user_id, target_thread_id).await?;
+        self.validate_react(user_id, target_thread_id).await?;
         if !self.votes_repo.is_reacted_thread(user_id, target_thread_id).await? {
             return Err(CustomError::NotReacted);
         }
-        self.votes_repo.react_cancel_thread(user_id, target_thread_id, reaction).await
+        self.votes_repo.react_cancel_thread(user_id, target_thread_id, reaction).await?;
+        self.publish_votes(target_thread_id).await;
+        Ok(())
+    }
+
+    // The new vote count for the clients viewing the thread.
+    async fn publish_votes(&self, thread_id: i64) {
+        match self.thread_repo.get_thread_by_id(thread_id).await {
+            Ok(thread) => {
+                self.event_bus.publish(Event::Votes { thread_id, votes: thread.votes })
+            }
+            Err(err) => error!("Failed to publish the votes of {}: {:?}", thread_id, err),
+        }
     }
 
     async fn validate_react(
         &self,
         user_id: i64,
         target_thread_id: i64,
-    ) -> Result<(), CustomError> {
+    ) -> Result<ResponseThread, CustomError> {
         let (user, thread) = tokio::join!(
             self.user_repo.find_user_by_id(user_id),
             self.thread_repo.get_thread_by_id(target_thread_id),
@@ -66,10 +125,13 @@ impl VotesService {
         if !user.is_profile_complete {
             return Err(CustomError::ProfileNotCreated);
         }
+        if config::env::envs().require_verified_email_to_vote && !user.is_email_verified {
+            return Err(CustomError::EmailNotVerified);
+        }
         if thread.is_deleted {
             return Err(CustomError::NotFound);
         }
 
-        Ok(())
+        Ok(thread)
     }
 }
@@ -0,0 +1,738 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Utc;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect, Client, Url,
+};
+use serde::Serialize;
+use serde_json::json;
+use tokio::{net::lookup_host, task::JoinSet};
+use tracing::error;
+
+use crate::{
+    config,
+    domain::{
+        dto::{
+            webhook::{
+                RequestCreateWebhook, RequestUpdateWebhook, ResponseCreatedWebhook,
+                ResponseWebhook, ResponseWebhookDelivery,
+            },
+            ResponsePage,
+        },
+        model::{
+            cursor_claims::{CursorClaims, CursorKind},
+            user::UserRole,
+            webhook::{
+                DueWebhookDelivery, Webhook, WebhookDelivery, WebhookDeliveryStatus,
+                WebhookEvent, WEBHOOK_SECRET_PREFIX,
+            },
+        },
+    },
+    error::CustomError,
+    repository::webhook_repo::WebhookRepositoryTrait,
+    utils::{self, crypto},
+};
+
+const MAX_WEBHOOKS_PER_USER: usize = 10;
+const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+const WEBHOOK_TIMEOUT_IN_SECONDS: u64 = 10;
+// Deliveries sent at once by the worker.
+const WEBHOOK_DELIVERY_BATCH_SIZE: i64 = 50;
+// Longer than the timeout, so a delivery is not sent twice while in flight.
+const WEBHOOK_DELIVERY_LEASE_IN_SECONDS: i64 = 60;
+const MAX_WEBHOOK_RETRY_DELAY_IN_SECONDS: i64 = 60 * 60 * 6;
+// Transport errors are cut to this length, a failed response only keeps its status.
+const MAX_WEBHOOK_ERROR_LENGTH: usize = 500;
+
+// Deliveries are sent from inside the network, so webhooks may only point to public
+// addresses. `allow_private_addresses` is for tests with a local receiver.
+pub struct WebhookService {
+    webhook_repo: Arc<dyn WebhookRepositoryTrait>,
+    client: Client,
+    allow_private_addresses: bool,
+}
+
+impl WebhookService {
+    pub fn new(webhook_repo: Arc<dyn WebhookRepositoryTrait>) -> Self {
+        Self::build(webhook_repo, false)
+    }
+
+    #[cfg(test)]
+    fn allowing_private_addresses(webhook_repo: Arc<dyn WebhookRepositoryTrait>) -> Self {
+        Self::build(webhook_repo, true)
+    }
+
+    fn build(
+        webhook_repo: Arc<dyn WebhookRepositoryTrait>,
+        allow_private_addresses: bool,
+    ) -> Self {
+        // receivers answer with a 2xx, a redirect is a failed delivery
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_IN_SECONDS))
+            .redirect(redirect::Policy::none())
+            .no_proxy();
+        if !allow_private_addresses {
+            builder = builder.dns_resolver(Arc::new(PublicAddressResolver));
+        }
+        let client = builder.build().expect("the webhook client configuration is valid");
+        Self { webhook_repo, client, allow_private_addresses }
+    }
+
+    pub async fn create_webhook(
+        &self,
+        user_id: i64,
+        role: UserRole,
+        webhook_dto: RequestCreateWebhook,
+    ) -> Result<ResponseCreatedWebhook, CustomError> {
+        let url = self.validate_url(&webhook_dto.url).await?;
+        let events = validate_events(&webhook_dto.events)?;
+        let all_users = webhook_dto.all_users.unwrap_or(false);
+        if all_users && role != UserRole::Admin {
+            return Err(CustomError::PermissionDenied(
+                "Only admins can receive the events of every user".to_string(),
+            ));
+        }
+        if self.webhook_repo.list_webhook(user_id).await?.len() >= MAX_WEBHOOKS_PER_USER {
+            return Err(CustomError::InvalidWebhook(format!(
+                "You can have up to {} webhooks",
+                MAX_WEBHOOKS_PER_USER
+            )));
+        }
+
+        let secret = format!("{}{}", WEBHOOK_SECRET_PREFIX, crypto::generate_token());
+        let webhook = self
+            .webhook_repo
+            .create_webhook(user_id, &url, &secret, events, all_users)
+            .await?;
+        Ok(ResponseCreatedWebhook { secret, webhook: to_response(webhook) })
+    }
+
+    pub async fn list_webhook(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<ResponseWebhook>, CustomError> {
+        let webhooks = self.webhook_repo.list_webhook(user_id).await?;
+        Ok(webhooks.into_iter().map(to_response).collect())
+    }
+
+    pub async fn update_webhook(
+        &self,
+        user_id: i64,
+        id: i64,
+        webhook_dto: RequestUpdateWebhook,
+    ) -> Result<ResponseWebhook, CustomError> {
+        let url = match webhook_dto.url.as_deref() {
+            Some(url) => Some(self.validate_url(url).await?),
+            None => None,
+        };
+        let events = webhook_dto.events.as_deref().map(validate_events).transpose()?;
+        let webhook = self
+            .webhook_repo
+            .update_webhook(id, user_id, url.as_deref(), events, webhook_dto.is_active)
+            .await?;
+        Ok(to_response(webhook))
+    }
+
+    pub async fn delete_webhook(&self, user_id: i64, id: i64) -> Result<(), CustomError> {
+        if !self.webhook_repo.delete_webhook(id, user_id).await? {
+            return Err(CustomError::NotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn list_delivery(
+        &self,
+        user_id: i64,
+        webhook_id: i64,
+        cursor: CursorClaims,
+        limit: i64,
+    ) -> Result<ResponsePage<ResponseWebhookDelivery>, CustomError> {
+        let webhook = self.webhook_repo.find_webhook(webhook_id, user_id).await?;
+        let deliveries =
+            self.webhook_repo.list_delivery(webhook.id, cursor, limit + 1).await?;
+        let (deliveries, next_cursor) =
+            utils::cursor::paginate(deliveries, limit, |delivery| {
+                CursorClaims::new(
+                    CursorKind::WebhookDelivery,
+                    delivery.created_at,
+                    delivery.id,
+                )
+            });
+        Ok(ResponsePage::new(
+            deliveries.into_iter().map(to_delivery_response).collect(),
+            next_cursor,
+        ))
+    }
+
+    pub async fn redeliver(
+        &self,
+        user_id: i64,
+        webhook_id: i64,
+        delivery_id: i64,
+    ) -> Result<ResponseWebhookDelivery, CustomError> {
+        let webhook = self.webhook_repo.find_webhook(webhook_id, user_id).await?;
+        let delivery = self.webhook_repo.redeliver(webhook.id, delivery_id).await?;
+        Ok(to_delivery_response(delivery))
+    }
+
+    // Queues `event` for the webhooks of `user_id` and those listening to every user.
+    // A failure is only logged, it must not fail the action that triggered it.
+    pub async fn dispatch(
+        &self,
+        user_id: i64,
+        event: WebhookEvent,
+        data: impl Serialize,
+    ) {
+        let payload = json!({
+            "event": event.as_str(),
+            "created_at": Utc::now(),
+            "data": data,
+        });
+        if let Err(err) =
+            self.webhook_repo.enqueue_delivery(user_id, event.as_str(), &payload).await
+        {
+            error!(
+                "Failed to queue the {} webhooks of user {}: {:?}",
+                event.as_str(),
+                user_id,
+                err
+            );
+        }
+    }
+
+    // Sends a batch of the due deliveries, returns how many were attempted.
+    pub async fn deliver_due_webhook(&self) -> Result<usize, CustomError> {
+        let deliveries = self
+            .webhook_repo
+            .claim_due_delivery(
+                WEBHOOK_DELIVERY_BATCH_SIZE,
+                WEBHOOK_DELIVERY_LEASE_IN_SECONDS,
+            )
+            .await?;
+        let attempted = deliveries.len();
+
+        let mut sending = JoinSet::new();
+        for delivery in deliveries {
+            let client = self.client.clone();
+            let webhook_repo = Arc::clone(&self.webhook_repo);
+            let allow_private_addresses = self.allow_private_addresses;
+            sending.spawn(async move {
+                send(client, webhook_repo, delivery, allow_private_addresses).await
+            });
+        }
+        while let Some(sent) = sending.join_next().await {
+            if let Err(err) = sent {
+                error!("Webhook delivery task failed: {:?}", err);
+            }
+        }
+        Ok(attempted)
+    }
+
+    async fn validate_url(&self, url: &str) -> Result<String, CustomError> {
+        let url = url.trim();
+        if url.len() > MAX_WEBHOOK_URL_LENGTH {
+            return Err(CustomError::InvalidWebhook(format!(
+                "Webhook URL must be at most {} characters",
+                MAX_WEBHOOK_URL_LENGTH
+            )));
+        }
+        let parsed = match Url::parse(url) {
+            Ok(parsed)
+                if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() =>
+            {
+                parsed
+            }
+            _ => {
+                return Err(CustomError::InvalidWebhook(
+                    "Webhook URL must be an absolute http(s) URL".to_string(),
+                ))
+            }
+        };
+        if !self.allow_private_addresses {
+            check_public_host(&parsed).await.map_err(CustomError::InvalidWebhook)?;
+        }
+        Ok(url.to_string())
+    }
+}
+
+// Resolves like the system resolver, but fails when the host has a non-public
+// address. The host is resolved again on every delivery, so one that was public
+// when registered cannot be pointed inside the network later (DNS rebinding).
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = lookup_host((name.as_str(), 0)).await?.collect();
+            if addrs.iter().any(|addr| !is_public_address(addr.ip())) {
+                return Err(format!(
+                    "{} resolves to a non-public address",
+                    name.as_str()
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+// Every address of the host of `url` must be public, IP literals included.
+async fn check_public_host(url: &Url) -> Result<(), String> {
+    let host = url.host_str().unwrap_or_default();
+    let addrs: Vec<IpAddr> =
+        match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => lookup_host((host, url.port_or_known_default().unwrap_or(0)))
+                .await
+                .map_err(|_| "Webhook URL host could not be resolved".to_string())?
+                .map(|addr| addr.ip())
+                .collect(),
+        };
+    if addrs.is_empty() || !addrs.into_iter().all(is_public_address) {
+        return Err("Webhook URL must point to a public address".to_string());
+    }
+    Ok(())
+}
+
+// Loopback, private, link-local, unspecified and other special-purpose ranges are
+// not reachable from the internet, webhooks must not reach them either.
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                // shared address space (carrier-grade NAT)
+                || (a == 100 && (b & 0xc0) == 64)
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b & 0xfe) == 18)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(ip));
+            }
+            // NAT64 reaches the embedded IPv4 address
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., a, b, c, d] = ip.octets();
+                return is_public_address(IpAddr::from([a, b, c, d]));
+            }
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // unique local, fc00::/7
+                || (segments[0] & 0xfe00) == 0xfc00
+                // link-local, fe80::/10
+                || (segments[0] & 0xffc0) == 0xfe80
+                // documentation, 2001:db8::/32
+                || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+        }
+    }
+}
+
+// POSTs the payload, signed over `{timestamp}.{body}` so a receiver can also reject
+// replayed requests. Anything but a 2xx is retried with an exponential backoff.
+// Only the status line of a failed response is kept, its body could be read back
+// from the delivery log.
+async fn send(
+    client: Client,
+    webhook_repo: Arc<dyn WebhookRepositoryTrait>,
+    delivery: DueWebhookDelivery,
+    allow_private_addresses: bool,
+) {
+    let (response_status, failure) =
+        match post(&client, &delivery, allow_private_addresses).await {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16() as i32), None)
+            }
+            Ok(response) => {
+                let status = response.status();
+                (Some(status.as_u16() as i32), Some(status.to_string()))
+            }
+            Err(err) => (None, Some(err)),
+        };
+
+    let saved = match (response_status, failure) {
+        (Some(status), None) => webhook_repo.complete_delivery(delivery.id, status).await,
+        (response_status, failure) => {
+            let failure: String = failure
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_WEBHOOK_ERROR_LENGTH)
+                .collect();
+            webhook_repo
+                .fail_delivery(
+                    delivery.id,
+                    response_status,
+                    &failure,
+                    retry_at(
+                        delivery.attempts,
+                        config::env::envs().webhook_max_attempts,
+                        config::env::envs().webhook_retry_base_in_seconds,
+                        Utc::now(),
+                    ),
+                )
+                .await
+        }
+    };
+    if let Err(err) = saved {
+        error!("Failed to save the webhook delivery {}: {:?}", delivery.id, err);
+    }
+}
+
+async fn post(
+    client: &Client,
+    delivery: &DueWebhookDelivery,
+    allow_private_addresses: bool,
+) -> Result<reqwest::Response, String> {
+    // the resolver only sees host names, IP literals are checked here
+    let url = Url::parse(&delivery.url).map_err(|err| err.to_string())?;
+    if !allow_private_addresses {
+        check_public_host(&url).await?;
+    }
+
+    let body = delivery.payload.to_string();
+    let timestamp = Utc::now().timestamp();
+    let signature =
+        crypto::sign_hmac_sha256(&delivery.secret, &format!("{}.{}", timestamp, body));
+    client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", delivery.id)
+        .header("X-Webhook-Event", &delivery.event)
+        .header("X-Webhook-Timestamp", timestamp)
+        .header("X-Webhook-Signature", format!("sha256={}", signature))
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| err.to_string())
+}
+
+// The delay doubles after every attempt, `None` once the attempts are used up.
+fn retry_at(
+    attempts: i32,
+    max_attempts: i64,
+    retry_base_in_seconds: i64,
+    now: chrono::DateTime<Utc>,
+) -> Option<chrono::DateTime<Utc>> {
+    if attempts as i64 >= max_attempts {
+        return None;
+    }
+    let delay = retry_base_in_seconds
+        .saturating_mul(1_i64 << (attempts - 1).clamp(0, 20))
+        .min(MAX_WEBHOOK_RETRY_DELAY_IN_SECONDS);
+    Some(now + chrono::Duration::seconds(delay))
+}
+
+fn validate_events(events: &[WebhookEvent]) -> Result<Vec<String>, CustomError> {
+    if events.is_empty() {
+        return Err(CustomError::InvalidWebhook(
+            "At least one event is required".to_string(),
+        ));
+    }
+    let mut events: Vec<String> =
+        events.iter().map(|event| event.as_str().to_string()).collect();
+    events.sort();
+    events.dedup();
+    Ok(events)
+}
+
+fn to_response(webhook: Webhook) -> ResponseWebhook {
+    ResponseWebhook {
+        id: webhook.id,
+        url: webhook.url,
+        events: webhook.events,
+        all_users: webhook.all_users,
+        is_active: webhook.is_active,
+        created_at: webhook.created_at,
+        updated_at: webhook.updated_at,
+    }
+}
+
+fn to_delivery_response(delivery: WebhookDelivery) -> ResponseWebhookDelivery {
+    let next_attempt_at = match delivery.status {
+        WebhookDeliveryStatus::Pending => Some(delivery.next_attempt_at),
+        _ => None,
+    };
+    ResponseWebhookDelivery {
+        id: delivery.id,
+        webhook_id: delivery.webhook_id,
+        event: delivery.event,
+        payload: delivery.payload,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        next_attempt_at,
+        response_status: delivery.response_status,
+        last_error: delivery.last_error,
+        redelivery_of: delivery.redelivery_of,
+        created_at: delivery.created_at,
+        delivered_at: delivery.delivered_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::{
+        extract::State, http::HeaderMap, http::StatusCode, routing::post, Router,
+    };
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::{
+        repository::webhook_repo::WebhookRepository,
+        test_utils::{self, create_user},
+    };
+
+    // Records the requests it gets and answers them with `status`.
+    #[derive(Clone)]
+    struct Receiver {
+        status: Arc<Mutex<StatusCode>>,
+        requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    }
+
+    impl Receiver {
+        async fn start() -> (Self, SocketAddr) {
+            let receiver = Receiver {
+                status: Arc::new(Mutex::new(StatusCode::OK)),
+                requests: Arc::new(Mutex::new(Vec::new())),
+            };
+            let app = Router::new()
+                .route(
+                    "/hook",
+                    post(
+                        |State(receiver): State<Receiver>,
+                         headers: HeaderMap,
+                         body: String| async move {
+                            receiver.requests.lock().unwrap().push((headers, body));
+                            (*receiver.status.lock().unwrap(), "internal details")
+                        },
+                    ),
+                )
+                .with_state(receiver.clone());
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            (receiver, addr)
+        }
+
+        fn answer_with(&self, status: StatusCode) {
+            *self.status.lock().unwrap() = status;
+        }
+
+        fn last_request(&self) -> (HeaderMap, String) {
+            self.requests.lock().unwrap().last().cloned().expect("no request received")
+        }
+    }
+
+    async fn list_delivery(
+        webhook_service: &WebhookService,
+        user_id: i64,
+        webhook_id: i64,
+    ) -> Vec<ResponseWebhookDelivery> {
+        let (cursor, limit) =
+            utils::cursor::preprocessing_cursor(None, None, CursorKind::WebhookDelivery)
+                .unwrap();
+        webhook_service
+            .list_delivery(user_id, webhook_id, cursor, limit)
+            .await
+            .unwrap()
+            .items
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap_and_gives_up() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let delay =
+            |attempts| retry_at(attempts, 8, 30, now).map(|at| (at - now).num_seconds());
+        assert_eq!(delay(1), Some(30));
+        assert_eq!(delay(2), Some(60));
+        assert_eq!(delay(3), Some(120));
+        assert_eq!(delay(7), Some(30 * 64));
+        assert_eq!(delay(8), None);
+        assert_eq!(delay(9), None);
+
+        let capped = retry_at(30, 100, 30, now).map(|at| (at - now).num_seconds());
+        assert_eq!(capped, Some(MAX_WEBHOOK_RETRY_DELAY_IN_SECONDS));
+    }
+
+    #[test]
+    fn only_public_addresses_are_allowed() {
+        for ip in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c", "1.1.1.1"] {
+            assert!(is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn private_hosts_are_rejected() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost/hook",
+        ] {
+            let url = Url::parse(url).unwrap();
+            assert!(check_public_host(&url).await.is_err(), "{}", url);
+        }
+        let url = Url::parse("https://93.184.215.14/hook").unwrap();
+        assert!(check_public_host(&url).await.is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn deliveries_are_signed_retried_given_up_and_redelivered() {
+        let db_pool = test_utils::test_pool().await;
+        let webhook_repo = Arc::new(WebhookRepository::new(Arc::new(db_pool.clone())));
+        let webhook_service = WebhookService::allowing_private_addresses(webhook_repo);
+        let (receiver, addr) = Receiver::start().await;
+        let (user_id, _) = create_user(&db_pool).await;
+
+        let webhook = webhook_service
+            .create_webhook(
+                user_id,
+                UserRole::User,
+                RequestCreateWebhook {
+                    url: format!("http://{}/hook", addr),
+                    events: vec![WebhookEvent::ThreadCreated],
+                    all_users: None,
+                },
+            )
+            .await
+            .unwrap();
+        let webhook_id = webhook.webhook.id;
+
+        // a failed attempt is retried later, without the response body
+        receiver.answer_with(StatusCode::INTERNAL_SERVER_ERROR);
+        webhook_service
+            .dispatch(user_id, WebhookEvent::ThreadCreated, json!({ "id": 1 }))
+            .await;
+        webhook_service.deliver_due_webhook().await.unwrap();
+        let deliveries = list_delivery(&webhook_service, user_id, webhook_id).await;
+        assert_eq!(deliveries.len(), 1);
+        let delivery = &deliveries[0];
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.response_status, Some(500));
+        assert_eq!(delivery.last_error.as_deref(), Some("500 Internal Server Error"));
+        assert!(delivery.next_attempt_at.unwrap() > Utc::now());
+
+        // the last attempt that fails gives up
+        sqlx::query(
+            "UPDATE webhook_deliveries SET attempts = $2, next_attempt_at = NOW() WHERE id = $1",
+        )
+        .bind(delivery.id)
+        .bind(config::env::envs().webhook_max_attempts as i32 - 1)
+        .execute(&db_pool)
+        .await
+        .unwrap();
+        webhook_service.deliver_due_webhook().await.unwrap();
+        let deliveries = list_delivery(&webhook_service, user_id, webhook_id).await;
+        assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Failed);
+        assert_eq!(deliveries[0].next_attempt_at, None);
+
+        // a redelivery is a new delivery of the same payload
+        receiver.answer_with(StatusCode::OK);
+        let redelivery =
+            webhook_service.redeliver(user_id, webhook_id, delivery.id).await.unwrap();
+        assert_eq!(redelivery.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(redelivery.redelivery_of, Some(delivery.id));
+        webhook_service.deliver_due_webhook().await.unwrap();
+        let deliveries = list_delivery(&webhook_service, user_id, webhook_id).await;
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[0].id, redelivery.id);
+        assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Succeeded);
+        assert_eq!(deliveries[0].response_status, Some(200));
+        assert!(deliveries[0].delivered_at.is_some());
+
+        // signed over `{timestamp}.{body}` with the secret handed out once
+        let (headers, body) = receiver.last_request();
+        let header = |name: &str| headers[name].to_str().unwrap().to_string();
+        assert_eq!(header("X-Webhook-Id"), redelivery.id.to_string());
+        assert_eq!(header("X-Webhook-Event"), "thread.created");
+        let signed = format!("{}.{}", header("X-Webhook-Timestamp"), body);
+        assert_eq!(
+            header("X-Webhook-Signature"),
+            format!("sha256={}", crypto::sign_hmac_sha256(&webhook.secret, &signed))
+        );
+        let payload: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(payload["event"], "thread.created");
+        assert_eq!(payload["data"], json!({ "id": 1 }));
+
+        test_utils::delete_users(&db_pool, &[user_id]).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn all_users_webhooks_stop_when_the_admin_is_demoted() {
+        let db_pool = test_utils::test_pool().await;
+        let webhook_repo = Arc::new(WebhookRepository::new(Arc::new(db_pool.clone())));
+        let webhook_service = WebhookService::allowing_private_addresses(webhook_repo);
+        let (admin_id, _) = create_user(&db_pool).await;
+        let (user_id, _) = create_user(&db_pool).await;
+        let set_role = |role: &'static str| {
+            sqlx::query("UPDATE users SET role = $2::user_role_enum WHERE id = $1")
+                .bind(admin_id)
+                .bind(role)
+                .execute(&db_pool)
+        };
+        set_role("ADMIN").await.unwrap();
+
+        let webhook = webhook_service
+            .create_webhook(
+                admin_id,
+                UserRole::Admin,
+                RequestCreateWebhook {
+                    // never delivered, the deliveries are only counted
+                    url: "http://127.0.0.1:9/hook".to_string(),
+                    events: vec![WebhookEvent::UserFollowed],
+                    all_users: Some(true),
+                },
+            )
+            .await
+            .unwrap();
+        let webhook_id = webhook.webhook.id;
+
+        webhook_service.dispatch(user_id, WebhookEvent::UserFollowed, json!({})).await;
+        assert_eq!(list_delivery(&webhook_service, admin_id, webhook_id).await.len(), 1);
+
+        set_role("USER").await.unwrap();
+        webhook_service.dispatch(user_id, WebhookEvent::UserFollowed, json!({})).await;
+        assert_eq!(list_delivery(&webhook_service, admin_id, webhook_id).await.len(), 1);
+        // their own events still count
+        webhook_service.dispatch(admin_id, WebhookEvent::UserFollowed, json!({})).await;
+        assert_eq!(list_delivery(&webhook_service, admin_id, webhook_id).await.len(), 2);
+
+        test_utils::delete_users(&db_pool, &[admin_id, user_id]).await;
+    }
+}
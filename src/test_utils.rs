@@ -0,0 +1,88 @@
+// Helpers of the database backed tests. They are `#[ignore]`d and run against
+// `TEST_DATABASE_URL`, a database with the migrations applied. Every test creates
+// its own users, deleting them removes what the test created.
+use sqlx::{postgres::PgPoolOptions, PgPool};
+
+use crate::{api::state::AppState, utils::crypto};
+
+pub async fn test_pool() -> PgPool {
+    let db_url = std::env::var("TEST_DATABASE_URL")
+        .expect("TEST_DATABASE_URL must be set to run the database backed tests");
+    PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&db_url)
+        .await
+        .expect("error connecting to TEST_DATABASE_URL")
+}
+
+pub fn app_state(db_pool: &PgPool) -> AppState {
+    crate::api::server::di(db_pool)
+}
+
+// A verified user with a complete profile, `handle` is random.
+pub async fn create_user(db_pool: &PgPool) -> (i64, String) {
+    let handle = format!("t{}", &crypto::hash_token(&crypto::generate_token())[..12]);
+    let user_id = sqlx::query_scalar::<_, i64>(
+        r#"
+        INSERT INTO users (
+            email, hash_password, name, handle, profile_img_url, bio,
+            is_profile_complete, is_email_verified, email_verified_at
+        )
+        VALUES ($1, 'unused', $2, $2, '', '', TRUE, TRUE, NOW())
+        RETURNING id
+        "#,
+    )
+    .bind(format!("{}@test.local", handle))
+    .bind(&handle)
+    .fetch_one(db_pool)
+    .await
+    .expect("error creating test user");
+    (user_id, handle)
+}
+
+pub async fn create_thread(
+    db_pool: &PgPool,
+    user_id: i64,
+    parent_thread: Option<i64>,
+) -> i64 {
+    sqlx::query_scalar::<_, i64>(
+        "INSERT INTO thread (user_id, content, parent_thread) VALUES ($1, 'test', $2) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(parent_thread)
+    .fetch_one(db_pool)
+    .await
+    .expect("error creating test thread")
+}
+
+// `reaction` is `UP` or `DOWN`, `None` removes the vote.
+pub async fn vote(
+    db_pool: &PgPool,
+    user_id: i64,
+    thread_id: i64,
+    reaction: Option<&str>,
+) {
+    let query = match reaction {
+        Some(reaction) => sqlx::query(
+            r#"
+            INSERT INTO votes (user_id, thread_id, reaction) VALUES ($1, $2, $3::reaction_enum)
+            ON CONFLICT (user_id, thread_id) DO UPDATE SET reaction = EXCLUDED.reaction
+            "#,
+        )
+        .bind(user_id)
+        .bind(thread_id)
+        .bind(reaction),
+        None => sqlx::query("DELETE FROM votes WHERE user_id = $1 AND thread_id = $2")
+            .bind(user_id)
+            .bind(thread_id),
+    };
+    query.execute(db_pool).await.expect("error voting");
+}
+
+pub async fn delete_users(db_pool: &PgPool, user_ids: &[i64]) {
+    sqlx::query("DELETE FROM users WHERE id = ANY($1)")
+        .bind(user_ids)
+        .execute(db_pool)
+        .await
+        .expect("error deleting test users");
+}
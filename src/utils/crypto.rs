@@ -1,27 +1,65 @@
-use bcrypt::{hash, verify};
+use std::sync::OnceLock;
 
-use crate::error::CustomError;
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
-// NOTE: Hashing cost (recommended range: 12 <= cost <= 14) for strong hashing security.
-// TODO: Move this to an environment variable.
-const HASHING_COST: u32 = 13;
+use crate::{error::CustomError, utils::password_hash};
 
+const TOKEN_BYTES: usize = 32;
+
+// Hashes with the configured algorithm (`PASSWORD_HASH_ALGORITHM`).
 pub fn hash_password(password: &str) -> Result<String, CustomError> {
-    let hashed_password = hash(password, HASHING_COST)
-        .map_err(|_| CustomError::InternalError("Password hashing failed".to_string()))?;
-    Ok(hashed_password)
+    password_hash::configured_scheme().hash(password)
 }
 
+// Verifies against any supported algorithm, whatever the current configuration is.
 pub fn verify_password(
     password: &str,
     hashed_password: &str,
 ) -> Result<bool, CustomError> {
-    let password_is_valid = verify(password, &hashed_password).map_err(|_| {
-        CustomError::InternalError("Password verification failed".to_string())
-    })?;
+    let password_is_valid =
+        password_hash::scheme_for(hashed_password)?.verify(password, hashed_password)?;
 
     if password_is_valid {
         return Ok(true);
     }
     Err(CustomError::InvalidCredentials)
 }
+
+// Burns the time of a real verification, so an unknown email takes as long to
+// reject as a wrong password.
+pub fn verify_dummy_password(password: &str) {
+    static DUMMY_HASH: OnceLock<Option<String>> = OnceLock::new();
+    let dummy_hash =
+        DUMMY_HASH.get_or_init(|| hash_password(&generate_token()).ok()).as_deref();
+    if let Some(dummy_hash) = dummy_hash {
+        let _ = verify_password(password, dummy_hash);
+    }
+}
+
+// Whether the hash should be replaced by one with the configured algorithm and cost.
+pub fn password_needs_rehash(hashed_password: &str) -> bool {
+    password_hash::configured_scheme().needs_rehash(hashed_password)
+}
+
+// Opaque random token handed to clients (e.g. refresh token).
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// Tokens are high-entropy random values, so a fast digest is enough to store them.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+// Hex encoded HMAC-SHA256, e.g. to sign webhook payloads.
+pub fn sign_hmac_sha256(secret: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take a key of any size");
+    mac.update(message.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
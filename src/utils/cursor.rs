@@ -1,12 +1,103 @@
-use crate::domain::model::cursor_claims::CursorClaims;
+use std::sync::OnceLock;
 
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    config,
+    domain::model::cursor_claims::{CursorClaims, CursorKind, CURSOR_VERSION},
+    error::CustomError,
+};
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 100;
+
+// Cursors are opaque to clients: `base64url(claims).base64url(HMAC-SHA256)`, signed
+// with `CURSOR_SECRET` (or `JWT_SECRET` when unset) so they cannot be forged.
+pub fn cursor_secret() -> &'static [u8] {
+    static INSTANCE: OnceLock<Vec<u8>> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        let envs = config::env::envs();
+        let secret = match envs.cursor_secret.as_str() {
+            "" => envs.jwt_secret.as_str(),
+            secret => secret,
+        };
+        if !envs.is_development() && secret == config::env::DEFAULT_JWT_SECRET {
+            panic!(
+                "Invalid cursor signing configuration: CURSOR_SECRET or JWT_SECRET must be set when APP_ENV is '{}'",
+                envs.app_env
+            );
+        }
+        secret.as_bytes().to_vec()
+    })
+}
+
+// Returns the decoded cursor (or the first page) and the page size.
 pub fn preprocessing_cursor(
     cursor: Option<&str>,
     limit: Option<i64>,
-) -> (CursorClaims, i64) {
-    let cursor = cursor.unwrap_or_default();
-    let claims = CursorClaims::decode_cursor(cursor).unwrap_or_default();
-    let limit = limit.unwrap_or(10);
+    kind: CursorKind,
+) -> Result<(CursorClaims, i64), CustomError> {
+    let claims = match cursor.filter(|cursor| !cursor.is_empty()) {
+        Some(cursor) => decode_cursor(cursor, kind)?,
+        None => CursorClaims::first_page(kind),
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    Ok((claims, limit))
+}
+
+// `items` is fetched with `limit + 1` rows, the extra row only tells whether there
+// is a next page. Returns the page and the cursor of its last item.
+pub fn paginate<T>(
+    mut items: Vec<T>,
+    limit: i64,
+    cursor_of: impl Fn(&T) -> CursorClaims,
+) -> (Vec<T>, Option<String>) {
+    let has_more = items.len() as i64 > limit;
+    items.truncate(limit as usize);
+    let next_cursor = match items.last() {
+        Some(last) if has_more => Some(encode_cursor(&cursor_of(last))),
+        _ => None,
+    };
+    (items, next_cursor)
+}
+
+pub fn encode_cursor(claims: &CursorClaims) -> String {
+    let payload = general_purpose::URL_SAFE_NO_PAD
+        .encode(serde_json::to_vec(claims).expect("cursor claims are serializable"));
+    let signature = general_purpose::URL_SAFE_NO_PAD.encode(sign(payload.as_bytes()));
+    format!("{}.{}", payload, signature)
+}
+
+fn decode_cursor(cursor: &str, kind: CursorKind) -> Result<CursorClaims, CustomError> {
+    let (payload, signature) =
+        cursor.split_once('.').ok_or(CustomError::InvalidCursor)?;
+    let signature = general_purpose::URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| CustomError::InvalidCursor)?;
+    mac()
+        .chain_update(payload.as_bytes())
+        .verify_slice(&signature)
+        .map_err(|_| CustomError::InvalidCursor)?;
+
+    let claims: CursorClaims = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|payload| serde_json::from_slice(&payload).ok())
+        .ok_or(CustomError::InvalidCursor)?;
+    if claims.version != CURSOR_VERSION || claims.kind != kind {
+        return Err(CustomError::InvalidCursor);
+    }
+    Ok(claims)
+}
+
+fn sign(payload: &[u8]) -> Vec<u8> {
+    mac().chain_update(payload).finalize().into_bytes().to_vec()
+}
 
-    (claims, limit)
+fn mac() -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(cursor_secret())
+        .expect("HMAC can take a key of any size")
 }
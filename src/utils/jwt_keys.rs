@@ -0,0 +1,322 @@
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose, Engine as _};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::{Error, ErrorKind},
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use pkcs1::{der::Decode, RsaPublicKey};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::config::{self, env::Envs};
+
+// Every JWT (access tokens, 2FA challenges) is signed and verified through this ring.
+// `JWT_KEYS` lists `kid:ALG:path` entries (ALG is RS256 or EdDSA, path a private key
+// PEM). New tokens are signed with `JWT_ACTIVE_KID`, the other keys are still
+// accepted so tokens issued before a rotation keep working until they expire.
+// Without `JWT_KEYS`, tokens are signed with HS256 and `JWT_SECRET`.
+pub fn key_ring() -> &'static JwtKeyRing {
+    static INSTANCE: OnceLock<JwtKeyRing> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        JwtKeyRing::load(config::env::envs())
+            .unwrap_or_else(|err| panic!("Invalid JWT signing configuration: {}", err))
+    })
+}
+
+struct JwtKey {
+    // `None` for the HS256 secret, tokens signed with it carry no `kid`
+    kid: Option<String>,
+    algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    // public part published in the JWKS, never set for the shared secret
+    jwk: Option<Value>,
+}
+
+pub struct JwtKeyRing {
+    keys: Vec<JwtKey>,
+    active_index: usize,
+}
+
+impl JwtKeyRing {
+    fn load(envs: &Envs) -> Result<Self, String> {
+        if envs.jwt_keys.trim().is_empty() {
+            if !envs.is_development()
+                && envs.jwt_secret == config::env::DEFAULT_JWT_SECRET
+            {
+                return Err(format!(
+                    "JWT_KEYS or JWT_SECRET must be set when APP_ENV is '{}'",
+                    envs.app_env
+                ));
+            }
+            let secret = envs.jwt_secret.as_bytes();
+            return Ok(Self {
+                keys: vec![JwtKey {
+                    kid: None,
+                    algorithm: Algorithm::HS256,
+                    encoding_key: EncodingKey::from_secret(secret),
+                    decoding_key: DecodingKey::from_secret(secret),
+                    jwk: None,
+                }],
+                active_index: 0,
+            });
+        }
+
+        let keys = envs
+            .jwt_keys
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(load_key)
+            .collect::<Result<Vec<JwtKey>, String>>()?;
+        if keys.is_empty() {
+            return Err("JWT_KEYS has no keys".to_string());
+        }
+
+        let active_kid = match envs.jwt_active_kid.trim() {
+            "" => keys[0].kid.clone().unwrap_or_default(),
+            active_kid => active_kid.to_string(),
+        };
+        let active_index = keys
+            .iter()
+            .position(|key| key.kid.as_deref() == Some(active_kid.as_str()))
+            .ok_or(format!(
+                "JWT_ACTIVE_KID '{}' is not listed in JWT_KEYS",
+                active_kid
+            ))?;
+
+        Ok(Self { keys, active_index })
+    }
+
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, Error> {
+        let key = &self.keys[self.active_index];
+        let mut header = Header::new(key.algorithm);
+        header.kid = key.kid.clone();
+        encode(&header, claims, &key.encoding_key)
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, token: &str) -> Result<T, Error> {
+        let header = decode_header(token)?;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.kid == header.kid)
+            .ok_or(Error::from(ErrorKind::InvalidKeyFormat))?;
+        decode::<T>(token, &key.decoding_key, &Validation::new(key.algorithm))
+            .map(|decoded_token| decoded_token.claims)
+    }
+
+    // JSON Web Key Set of every public key, served at `/.well-known/jwks.json`.
+    pub fn jwks(&self) -> Value {
+        let keys: Vec<&Value> =
+            self.keys.iter().filter_map(|key| key.jwk.as_ref()).collect();
+        json!({ "keys": keys })
+    }
+}
+
+fn load_key(entry: &str) -> Result<JwtKey, String> {
+    let mut parts = entry.splitn(3, ':');
+    let (Some(kid), Some(algorithm), Some(path)) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("'{}' must be in the form kid:ALG:path", entry));
+    };
+    let pem_bytes = std::fs::read(path)
+        .map_err(|err| format!("Cannot read key '{}': {}", path, err))?;
+    let der = pem::parse(&pem_bytes)
+        .map_err(|err| format!("Key '{}' is not a valid PEM: {}", kid, err))?;
+
+    let (algorithm, encoding_key, decoding_key, jwk) = match algorithm {
+        "RS256" => {
+            let key_pair = match der.tag() {
+                "RSA PRIVATE KEY" => RsaKeyPair::from_der(der.contents()),
+                _ => RsaKeyPair::from_pkcs8(der.contents()),
+            }
+            .map_err(|err| {
+                format!("Key '{}' is not a valid RSA private key: {}", kid, err)
+            })?;
+            let public_key = RsaPublicKey::from_der(key_pair.public_key().as_ref())
+                .map_err(|err| {
+                    format!("Key '{}' has an invalid public key: {}", kid, err)
+                })?;
+            let n = base64_url(public_key.modulus.as_bytes());
+            let e = base64_url(public_key.public_exponent.as_bytes());
+
+            let encoding_key = EncodingKey::from_rsa_pem(&pem_bytes)
+                .map_err(|err| format!("Key '{}': {}", kid, err))?;
+            let decoding_key = DecodingKey::from_rsa_components(&n, &e)
+                .map_err(|err| format!("Key '{}': {}", kid, err))?;
+            let jwk = json!({
+                "kty": "RSA",
+                "use": "sig",
+                "alg": "RS256",
+                "kid": kid,
+                "n": n,
+                "e": e,
+            });
+            (Algorithm::RS256, encoding_key, decoding_key, jwk)
+        }
+        "EdDSA" => {
+            let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(der.contents())
+                .map_err(|err| {
+                    format!("Key '{}' is not a valid Ed25519 private key: {}", kid, err)
+                })?;
+            let x = base64_url(key_pair.public_key().as_ref());
+
+            let encoding_key = EncodingKey::from_ed_pem(&pem_bytes)
+                .map_err(|err| format!("Key '{}': {}", kid, err))?;
+            let decoding_key = DecodingKey::from_ed_components(&x)
+                .map_err(|err| format!("Key '{}': {}", kid, err))?;
+            let jwk = json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "use": "sig",
+                "alg": "EdDSA",
+                "kid": kid,
+                "x": x,
+            });
+            (Algorithm::EdDSA, encoding_key, decoding_key, jwk)
+        }
+        _ => {
+            return Err(format!(
+                "Key '{}' uses unsupported algorithm '{}', expected RS256 or EdDSA",
+                kid, algorithm
+            ))
+        }
+    };
+
+    Ok(JwtKey {
+        kid: Some(kid.to_string()),
+        algorithm,
+        encoding_key,
+        decoding_key,
+        jwk: Some(jwk),
+    })
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::Utc;
+    use ring::rand::SystemRandom;
+
+    use super::*;
+
+    fn envs(jwt_keys: &str, jwt_active_kid: &str, app_env: &str) -> Envs {
+        Envs {
+            app_env: app_env.to_string(),
+            jwt_secret: config::env::DEFAULT_JWT_SECRET.to_string(),
+            jwt_keys: jwt_keys.to_string(),
+            jwt_active_kid: jwt_active_kid.to_string(),
+            ..Envs::new()
+        }
+    }
+
+    // Writes a fresh Ed25519 private key PEM, returns the `kid:EdDSA:path` entry.
+    fn ed25519_key(kid: &str) -> String {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem = pem::encode(&pem::Pem::new("PRIVATE KEY", pkcs8.as_ref().to_vec()));
+        let path: PathBuf = std::env::temp_dir().join(format!(
+            "jwt-key-{}-{}-{}.pem",
+            kid,
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        std::fs::write(&path, pem).unwrap();
+        format!("{}:EdDSA:{}", kid, path.display())
+    }
+
+    fn claims() -> Value {
+        json!({ "sub": "1", "exp": Utc::now().timestamp() + 60 })
+    }
+
+    fn kid_of(token: &str) -> Option<String> {
+        decode_header(token).unwrap().kid
+    }
+
+    #[test]
+    fn shared_secret_round_trips_in_development() {
+        let ring = JwtKeyRing::load(&envs("", "", "development")).unwrap();
+        let token = ring.encode(&claims()).unwrap();
+        assert_eq!(kid_of(&token), None);
+        assert_eq!(ring.decode::<Value>(&token).unwrap()["sub"], "1");
+        assert_eq!(ring.jwks(), json!({ "keys": [] }));
+    }
+
+    #[test]
+    fn default_secret_is_rejected_outside_development() {
+        assert!(JwtKeyRing::load(&envs("", "", "production")).is_err());
+        assert!(JwtKeyRing::load(&envs("", "", "staging")).is_err());
+    }
+
+    #[test]
+    fn rotated_key_ring_verifies_tokens_of_the_previous_key() {
+        let old_key = ed25519_key("old");
+        let new_key = ed25519_key("new");
+        let before_rotation =
+            JwtKeyRing::load(&envs(&old_key, "", "production")).unwrap();
+        let old_token = before_rotation.encode(&claims()).unwrap();
+        assert_eq!(kid_of(&old_token).as_deref(), Some("old"));
+
+        let keys = format!("{},{}", old_key, new_key);
+        let after_rotation = JwtKeyRing::load(&envs(&keys, "new", "production")).unwrap();
+        let new_token = after_rotation.encode(&claims()).unwrap();
+        assert_eq!(kid_of(&new_token).as_deref(), Some("new"));
+        assert!(after_rotation.decode::<Value>(&old_token).is_ok());
+        assert!(after_rotation.decode::<Value>(&new_token).is_ok());
+        assert!(before_rotation.decode::<Value>(&new_token).is_err());
+
+        let jwks = after_rotation.jwks();
+        let kids: Vec<&Value> =
+            jwks["keys"].as_array().unwrap().iter().map(|jwk| &jwk["kid"]).collect();
+        assert_eq!(kids, [&json!("old"), &json!("new")]);
+
+        // once the old key is dropped its tokens stop verifying
+        let retired = JwtKeyRing::load(&envs(&new_key, "", "production")).unwrap();
+        assert!(retired.decode::<Value>(&old_token).is_err());
+        assert!(retired.decode::<Value>(&new_token).is_ok());
+    }
+
+    #[test]
+    fn tokens_of_another_key_with_the_same_kid_are_rejected() {
+        let ring =
+            JwtKeyRing::load(&envs(&ed25519_key("main"), "", "production")).unwrap();
+        let impostor =
+            JwtKeyRing::load(&envs(&ed25519_key("main"), "", "production")).unwrap();
+        let token = impostor.encode(&claims()).unwrap();
+        assert!(ring.decode::<Value>(&token).is_err());
+    }
+
+    #[test]
+    fn shared_secret_tokens_are_rejected_by_a_key_ring() {
+        let secret_ring = JwtKeyRing::load(&envs("", "", "development")).unwrap();
+        let token = secret_ring.encode(&claims()).unwrap();
+        let ring =
+            JwtKeyRing::load(&envs(&ed25519_key("main"), "", "production")).unwrap();
+        assert!(ring.decode::<Value>(&token).is_err());
+    }
+
+    #[test]
+    fn invalid_key_ring_configuration_is_rejected() {
+        let key = ed25519_key("main");
+        assert!(JwtKeyRing::load(&envs(&key, "missing", "production")).is_err());
+        assert!(
+            JwtKeyRing::load(&envs("main:HS256:/dev/null", "", "production")).is_err()
+        );
+        assert!(JwtKeyRing::load(&envs("main:EdDSA", "", "production")).is_err());
+        assert!(JwtKeyRing::load(&envs("main:EdDSA:/nonexistent.pem", "", "production"))
+            .is_err());
+        assert_eq!(
+            JwtKeyRing::load(&envs(" , ", "", "production")).err().as_deref(),
+            Some("JWT_KEYS has no keys")
+        );
+    }
+}
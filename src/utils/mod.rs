@@ -1,2 +1,6 @@
 pub mod crypto;
 pub mod cursor;
+pub mod jwt_keys;
+pub mod password_hash;
+pub mod text;
+pub mod totp;
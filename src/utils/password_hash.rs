@@ -0,0 +1,280 @@
+use std::sync::OnceLock;
+
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use rand::rngs::OsRng;
+
+use crate::{
+    config::{self, env::Envs},
+    error::CustomError,
+};
+
+const MIN_BCRYPT_COST: u32 = 4;
+const MAX_BCRYPT_COST: u32 = 31;
+
+// A password hashing algorithm. Every stored hash is self-describing (PHC / modular
+// crypt format), so the scheme that produced it can be found from the hash alone.
+pub trait PasswordHashScheme: Send + Sync {
+    fn hash(&self, password: &str) -> Result<String, CustomError>;
+    fn verify(&self, password: &str, hashed_password: &str) -> Result<bool, CustomError>;
+    // whether `hashed_password` was produced by this algorithm
+    fn recognizes(&self, hashed_password: &str) -> bool;
+    // whether `hashed_password` uses other parameters than the configured ones
+    fn is_outdated(&self, hashed_password: &str) -> bool;
+
+    // whether `hashed_password` should be replaced by a hash of this scheme
+    fn needs_rehash(&self, hashed_password: &str) -> bool {
+        !self.recognizes(hashed_password) || self.is_outdated(hashed_password)
+    }
+}
+
+pub struct Argon2idScheme {
+    params: Params,
+}
+
+impl Argon2idScheme {
+    pub fn new(
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    ) -> Result<Self, String> {
+        let params = Params::new(memory_kib, iterations, parallelism, None)
+            .map_err(|err| format!("Invalid Argon2 parameters: {}", err))?;
+        Ok(Self { params })
+    }
+
+    fn argon2(&self) -> Argon2<'_> {
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHashScheme for Argon2idScheme {
+    fn hash(&self, password: &str) -> Result<String, CustomError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hashed_password =
+            self.argon2().hash_password(password.as_bytes(), &salt).map_err(|_| {
+                CustomError::InternalError("Password hashing failed".to_string())
+            })?;
+        Ok(hashed_password.to_string())
+    }
+
+    fn verify(&self, password: &str, hashed_password: &str) -> Result<bool, CustomError> {
+        let parsed_hash = PasswordHash::new(hashed_password).map_err(|_| {
+            CustomError::InternalError("Password verification failed".to_string())
+        })?;
+        // the parameters stored in the hash are used, not the configured ones
+        Ok(self.argon2().verify_password(password.as_bytes(), &parsed_hash).is_ok())
+    }
+
+    fn recognizes(&self, hashed_password: &str) -> bool {
+        hashed_password.starts_with("$argon2id$")
+    }
+
+    fn is_outdated(&self, hashed_password: &str) -> bool {
+        let Ok(parsed_hash) = PasswordHash::new(hashed_password) else {
+            return true;
+        };
+        match Params::try_from(&parsed_hash) {
+            Ok(params) => {
+                params.m_cost() != self.params.m_cost()
+                    || params.t_cost() != self.params.t_cost()
+                    || params.p_cost() != self.params.p_cost()
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+pub struct BcryptScheme {
+    cost: u32,
+}
+
+impl BcryptScheme {
+    pub fn new(cost: u32) -> Result<Self, String> {
+        if !(MIN_BCRYPT_COST..=MAX_BCRYPT_COST).contains(&cost) {
+            return Err(format!(
+                "BCRYPT_COST must be between {} and {}",
+                MIN_BCRYPT_COST, MAX_BCRYPT_COST
+            ));
+        }
+        Ok(Self { cost })
+    }
+}
+
+impl PasswordHashScheme for BcryptScheme {
+    fn hash(&self, password: &str) -> Result<String, CustomError> {
+        bcrypt::hash(password, self.cost).map_err(|_| {
+            CustomError::InternalError("Password hashing failed".to_string())
+        })
+    }
+
+    fn verify(&self, password: &str, hashed_password: &str) -> Result<bool, CustomError> {
+        bcrypt::verify(password, hashed_password).map_err(|_| {
+            CustomError::InternalError("Password verification failed".to_string())
+        })
+    }
+
+    fn recognizes(&self, hashed_password: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| hashed_password.starts_with(prefix))
+    }
+
+    // e.g. `$2b$13$...`, the cost is the second field
+    fn is_outdated(&self, hashed_password: &str) -> bool {
+        hashed_password
+            .split('$')
+            .nth(2)
+            .and_then(|cost| cost.parse::<u32>().ok())
+            .is_none_or(|cost| cost != self.cost)
+    }
+}
+
+// The scheme new hashes are created with, from `PASSWORD_HASH_ALGORITHM`. Like the
+// JWT key ring, an invalid configuration is a startup error.
+pub fn configured_scheme() -> &'static dyn PasswordHashScheme {
+    static INSTANCE: OnceLock<Box<dyn PasswordHashScheme>> = OnceLock::new();
+    INSTANCE
+        .get_or_init(|| {
+            scheme_from_envs(config::env::envs()).unwrap_or_else(|err| {
+                panic!("Invalid password hashing configuration: {}", err)
+            })
+        })
+        .as_ref()
+}
+
+fn scheme_from_envs(envs: &Envs) -> Result<Box<dyn PasswordHashScheme>, String> {
+    match envs.password_hash_algorithm.as_str() {
+        "argon2id" => Ok(Box::new(Argon2idScheme::new(
+            env_as_u32("ARGON2_MEMORY_KIB", envs.argon2_memory_kib)?,
+            env_as_u32("ARGON2_ITERATIONS", envs.argon2_iterations)?,
+            env_as_u32("ARGON2_PARALLELISM", envs.argon2_parallelism)?,
+        )?)),
+        "bcrypt" => {
+            Ok(Box::new(BcryptScheme::new(env_as_u32("BCRYPT_COST", envs.bcrypt_cost)?)?))
+        }
+        algorithm => Err(format!(
+            "PASSWORD_HASH_ALGORITHM '{}' is not supported, expected argon2id or bcrypt",
+            algorithm
+        )),
+    }
+}
+
+fn env_as_u32(key: &str, value: i64) -> Result<u32, String> {
+    u32::try_from(value).map_err(|_| format!("{} must be a positive number", key))
+}
+
+// Every supported scheme, used to verify hashes created with another configuration.
+// Verification reads the parameters from the hash, so the defaults do here.
+pub fn scheme_for(
+    hashed_password: &str,
+) -> Result<Box<dyn PasswordHashScheme>, CustomError> {
+    let schemes: [Box<dyn PasswordHashScheme>; 2] = [
+        Box::new(Argon2idScheme { params: Params::default() }),
+        Box::new(BcryptScheme { cost: bcrypt::DEFAULT_COST }),
+    ];
+    schemes
+        .into_iter()
+        .find(|scheme| scheme.recognizes(hashed_password))
+        .ok_or(CustomError::InternalError("Unknown password hash format".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // cheap parameters, the tests only check the behavior
+    fn argon2id(memory_kib: u32) -> Argon2idScheme {
+        Argon2idScheme::new(memory_kib, 1, 1).unwrap()
+    }
+
+    fn envs(algorithm: &str, bcrypt_cost: i64, argon2_memory_kib: i64) -> Envs {
+        Envs {
+            password_hash_algorithm: algorithm.to_string(),
+            bcrypt_cost,
+            argon2_memory_kib,
+            argon2_iterations: 1,
+            argon2_parallelism: 1,
+            ..Envs::new()
+        }
+    }
+
+    #[test]
+    fn argon2id_round_trips() {
+        let scheme = argon2id(1024);
+        let hashed_password = scheme.hash("correct horse").unwrap();
+        assert!(hashed_password.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+        assert!(scheme.verify("correct horse", &hashed_password).unwrap());
+        assert!(!scheme.verify("battery staple", &hashed_password).unwrap());
+        assert!(!scheme.needs_rehash(&hashed_password));
+    }
+
+    #[test]
+    fn bcrypt_round_trips() {
+        let scheme = BcryptScheme::new(4).unwrap();
+        let hashed_password = scheme.hash("correct horse").unwrap();
+        assert!(hashed_password.starts_with("$2b$04$"));
+        assert!(scheme.verify("correct horse", &hashed_password).unwrap());
+        assert!(!scheme.verify("battery staple", &hashed_password).unwrap());
+        assert!(!scheme.needs_rehash(&hashed_password));
+    }
+
+    #[test]
+    fn hashes_are_verified_whatever_the_configuration() {
+        for hashed_password in [
+            argon2id(2048).hash("correct horse").unwrap(),
+            BcryptScheme::new(5).unwrap().hash("correct horse").unwrap(),
+        ] {
+            let scheme = scheme_for(&hashed_password).unwrap();
+            assert!(scheme.verify("correct horse", &hashed_password).unwrap());
+            assert!(!scheme.verify("battery staple", &hashed_password).unwrap());
+        }
+        assert!(scheme_for("plain text").is_err());
+    }
+
+    #[test]
+    fn other_algorithm_or_parameters_need_a_rehash_on_login() {
+        let bcrypt_hash = BcryptScheme::new(4).unwrap().hash("correct horse").unwrap();
+        let argon2id_hash = argon2id(1024).hash("correct horse").unwrap();
+
+        assert!(argon2id(1024).needs_rehash(&bcrypt_hash));
+        assert!(argon2id(2048).needs_rehash(&argon2id_hash));
+        assert!(BcryptScheme::new(4).unwrap().needs_rehash(&argon2id_hash));
+        assert!(BcryptScheme::new(5).unwrap().needs_rehash(&bcrypt_hash));
+
+        // what signin does: verify the old hash, then store one of the configured scheme
+        let configured = argon2id(1024);
+        assert!(scheme_for(&bcrypt_hash)
+            .unwrap()
+            .verify("correct horse", &bcrypt_hash)
+            .unwrap());
+        let rehashed = configured.hash("correct horse").unwrap();
+        assert!(!configured.needs_rehash(&rehashed));
+        assert!(scheme_for(&rehashed)
+            .unwrap()
+            .verify("correct horse", &rehashed)
+            .unwrap());
+    }
+
+    #[test]
+    fn valid_configuration_is_accepted() {
+        let scheme = scheme_from_envs(&envs("argon2id", 13, 1024)).unwrap();
+        assert!(scheme.recognizes("$argon2id$v=19$m=1024,t=1,p=1$c2FsdA$aGFzaA"));
+        let scheme = scheme_from_envs(&envs("bcrypt", 4, 1024)).unwrap();
+        assert!(scheme.recognizes("$2b$04$"));
+    }
+
+    #[test]
+    fn invalid_configuration_is_rejected() {
+        assert!(scheme_from_envs(&envs("argon2", 13, 1024)).is_err());
+        assert!(scheme_from_envs(&envs("md5", 13, 1024)).is_err());
+        assert!(scheme_from_envs(&envs("bcrypt", -1, 1024)).is_err());
+        assert!(scheme_from_envs(&envs("bcrypt", 3, 1024)).is_err());
+        assert!(scheme_from_envs(&envs("bcrypt", 32, 1024)).is_err());
+        assert!(scheme_from_envs(&envs("argon2id", 13, -1024)).is_err());
+        assert!(scheme_from_envs(&envs("argon2id", 13, 1 << 33)).is_err());
+        assert!(scheme_from_envs(&envs("argon2id", 13, 0)).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+const MAX_TAG_LENGTH: usize = 50;
+const MAX_TAGS_PER_THREAD: usize = 10;
+const MAX_MENTIONS_PER_THREAD: usize = 10;
+
+// `@handle` in a content, `start`..`end` are character offsets of the whole
+// mention including the `@`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionSpan {
+    pub handle: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Words right after `sigil`, e.g. `#rust` or `@handle`. The sigil has to start the
+// text or follow a non-word character, so `a#b` and `mail@host` are skipped.
+// Returns the byte offset of the sigil and the word without it.
+fn scan_prefixed(content: &str, sigil: char) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = content.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == sigil && !prev.is_some_and(is_word_char) {
+            let word_start = start + c.len_utf8();
+            let mut word_end = word_start;
+            while let Some(&(i, next)) = chars.peek() {
+                if !is_word_char(next) {
+                    break;
+                }
+                word_end = i + next.len_utf8();
+                chars.next();
+            }
+            if word_end > word_start {
+                words.push((start, &content[word_start..word_end]));
+                prev = content[..word_end].chars().next_back();
+                continue;
+            }
+        }
+        prev = Some(c);
+    }
+    words
+}
+
+// `#Rust` and `#rust` are the same tag. A tag needs a letter, so `#1` is not one.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    let is_valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_LENGTH
+        && tag.chars().all(is_word_char)
+        && tag.chars().any(char::is_alphabetic);
+    is_valid.then(|| tag.to_lowercase())
+}
+
+// The distinct tags of a thread in order of appearance, at most `MAX_TAGS_PER_THREAD`.
+pub fn parse_tags(content: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for (_, word) in scan_prefixed(content, '#') {
+        if let Some(tag) = normalize_tag(word) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() == MAX_TAGS_PER_THREAD {
+            break;
+        }
+    }
+    tags
+}
+
+// Every mention of at most `MAX_MENTIONS_PER_THREAD` distinct handles, in order of
+// appearance. Handles keep the `@` they are stored with.
+pub fn parse_mentions(content: &str) -> Vec<MentionSpan> {
+    let mut handles: Vec<&str> = Vec::new();
+    let mut mentions = Vec::new();
+    for (byte_start, word) in scan_prefixed(content, '@') {
+        if !handles.contains(&word) {
+            if handles.len() == MAX_MENTIONS_PER_THREAD {
+                continue;
+            }
+            handles.push(word);
+        }
+        let start = content[..byte_start].chars().count();
+        mentions.push(MentionSpan {
+            handle: format!("@{}", word),
+            start,
+            end: start + 1 + word.chars().count(),
+        });
+    }
+    mentions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handles(content: &str) -> Vec<String> {
+        parse_mentions(content).into_iter().map(|mention| mention.handle).collect()
+    }
+
+    #[test]
+    fn mention_spans_are_character_offsets() {
+        let mentions = parse_mentions("héllo @bob and @ünïcode!");
+        assert_eq!(
+            mentions,
+            vec![
+                MentionSpan { handle: "@bob".to_string(), start: 6, end: 10 },
+                MentionSpan { handle: "@ünïcode".to_string(), start: 15, end: 23 },
+            ]
+        );
+    }
+
+    #[test]
+    fn email_like_text_is_not_a_mention() {
+        assert!(handles("write to a@b or mail@host.com").is_empty());
+        assert_eq!(handles("a@b @c"), vec!["@c"]);
+        assert!(handles("@ alone and @@").is_empty());
+    }
+
+    #[test]
+    fn punctuation_ends_and_precedes_a_mention() {
+        assert_eq!(
+            handles("(@bob), @alice. @carol's \"@dave\" @eve_1!"),
+            vec!["@bob", "@alice", "@carol", "@dave", "@eve_1"]
+        );
+        assert_eq!(handles("@a@b"), vec!["@a"]);
+    }
+
+    #[test]
+    fn repeated_mentions_keep_every_span_and_count_once() {
+        let mentions = parse_mentions("@bob @bob");
+        assert_eq!(mentions.len(), 2);
+        assert_eq!((mentions[1].start, mentions[1].end), (5, 9));
+
+        let content = (0..12).map(|i| format!("@u{} ", i)).collect::<String>() + "@u0";
+        let parsed = handles(&content);
+        assert_eq!(parsed.len(), MAX_MENTIONS_PER_THREAD + 1);
+        assert!(!parsed.contains(&"@u10".to_string()));
+        assert_eq!(parsed.last().map(String::as_str), Some("@u0"));
+    }
+
+    #[test]
+    fn mentions_keep_the_case_they_are_written_in() {
+        assert_eq!(handles("@Alice @alice"), vec!["@Alice", "@alice"]);
+    }
+}
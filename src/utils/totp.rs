@@ -0,0 +1,250 @@
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+
+// RFC 6238 defaults, the only parameters most authenticator apps support.
+const SECRET_BYTES: usize = 20;
+const TIME_STEP_IN_SECONDS: u64 = 30;
+const CODE_DIGITS: u32 = 6;
+// Accept the previous and the next code as well to tolerate clock drift.
+const ALLOWED_STEP_DRIFT: i64 = 1;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+// e.g. `K3PQA-7XZ2M`, typed by hand when the authenticator is lost.
+pub fn generate_recovery_code() -> String {
+    let mut random = [0u8; RECOVERY_CODE_LENGTH];
+    OsRng.fill_bytes(&mut random);
+    let code: String =
+        random.iter().map(|b| BASE32_ALPHABET[(*b & 0x1f) as usize] as char).collect();
+    format!("{}-{}", &code[..RECOVERY_CODE_LENGTH / 2], &code[RECOVERY_CODE_LENGTH / 2..])
+}
+
+pub fn normalize_recovery_code(code: &str) -> String {
+    let code: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if code.len() != RECOVERY_CODE_LENGTH {
+        return code;
+    }
+    format!("{}-{}", &code[..RECOVERY_CODE_LENGTH / 2], &code[RECOVERY_CODE_LENGTH / 2..])
+}
+
+// `otpauth://` URI rendered as a QR code by the client.
+pub fn provisioning_uri(secret: &str, account_name: &str, issuer: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        secret,
+        percent_encode(issuer),
+        CODE_DIGITS,
+        TIME_STEP_IN_SECONDS
+    )
+}
+
+// Returns the time step the code belongs to, so the caller can reject a code that
+// has already been used (`last_used_step`).
+pub fn verify_code(
+    secret: &str,
+    code: &str,
+    unix_time: u64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != CODE_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current_step = (unix_time / TIME_STEP_IN_SECONDS) as i64;
+
+    (-ALLOWED_STEP_DRIFT..=ALLOWED_STEP_DRIFT)
+        .map(|drift| current_step + drift)
+        .filter(|step| *step >= 0 && last_used_step.is_none_or(|last| *step > last))
+        .find(|step| generate_code(&key, *step as u64) == code)
+}
+
+fn generate_code(key: &[u8], step: u64) -> String {
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // dynamic truncation (RFC 4226 section 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!("{:0width$}", binary % 10u32.pow(CODE_DIGITS), width = CODE_DIGITS as usize)
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').chars() {
+        let value =
+            BASE32_ALPHABET.iter().position(|a| *a as char == c.to_ascii_uppercase())?
+                as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'@' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA1 seed, the last 6 of the 8 published digits.
+    const RFC_6238_SECRET: &[u8] = b"12345678901234567890";
+    const RFC_6238_VECTORS: [(u64, &str); 6] = [
+        (59, "287082"),
+        (1111111109, "081804"),
+        (1111111111, "050471"),
+        (1234567890, "005924"),
+        (2000000000, "279037"),
+        (20000000000, "353130"),
+    ];
+
+    #[test]
+    fn generate_code_matches_rfc_6238() {
+        for (unix_time, code) in RFC_6238_VECTORS {
+            let step = unix_time / TIME_STEP_IN_SECONDS;
+            assert_eq!(generate_code(RFC_6238_SECRET, step), code, "t = {unix_time}");
+        }
+    }
+
+    #[test]
+    fn verify_code_accepts_rfc_6238_codes() {
+        let secret = base32_encode(RFC_6238_SECRET);
+        for (unix_time, code) in RFC_6238_VECTORS {
+            assert_eq!(
+                verify_code(&secret, code, unix_time, None),
+                Some((unix_time / TIME_STEP_IN_SECONDS) as i64)
+            );
+        }
+    }
+
+    #[test]
+    fn verify_code_tolerates_one_step_of_drift() {
+        let secret = base32_encode(RFC_6238_SECRET);
+        assert_eq!(verify_code(&secret, "081804", 1111111109 + 30, None), Some(37037036));
+        assert_eq!(verify_code(&secret, "081804", 1111111109 - 30, None), Some(37037036));
+        assert_eq!(verify_code(&secret, "081804", 1111111109 + 60, None), None);
+    }
+
+    #[test]
+    fn verify_code_rejects_reused_and_malformed_codes() {
+        let secret = base32_encode(RFC_6238_SECRET);
+        assert_eq!(verify_code(&secret, "081804", 1111111109, Some(37037036)), None);
+        assert_eq!(verify_code(&secret, "81804", 1111111109, None), None);
+        assert_eq!(verify_code(&secret, "08180a", 1111111109, None), None);
+        assert_eq!(verify_code("not base32!", "081804", 1111111109, None), None);
+    }
+
+    // RFC 4648 section 10, without the padding.
+    const RFC_4648_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "MY"),
+        ("fo", "MZXQ"),
+        ("foo", "MZXW6"),
+        ("foob", "MZXW6YQ"),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI"),
+    ];
+
+    #[test]
+    fn base32_encode_matches_rfc_4648() {
+        for (data, encoded) in RFC_4648_VECTORS {
+            assert_eq!(base32_encode(data.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn base32_decode_matches_rfc_4648() {
+        for (data, encoded) in RFC_4648_VECTORS {
+            assert_eq!(base32_decode(encoded).unwrap(), data.as_bytes());
+        }
+        assert_eq!(base32_decode("MZXW6YQ=").unwrap(), b"foob");
+        assert_eq!(base32_decode("mzxw6ytboi").unwrap(), b"foobar");
+        assert_eq!(base32_decode("MZXW1"), None);
+    }
+
+    #[test]
+    fn generated_secret_round_trips() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_BYTES);
+    }
+
+    #[test]
+    fn provisioning_uri_percent_encodes_labels() {
+        assert_eq!(
+            provisioning_uri("JBSWY3DPEHPK3PXP", "alice@example.com", "Acme Co"),
+            "otpauth://totp/Acme%20Co:alice@example.com?secret=JBSWY3DPEHPK3PXP\
+             &issuer=Acme%20Co&algorithm=SHA1&digits=6&period=30"
+        );
+        assert_eq!(percent_encode("a:b/c?d&e=f"), "a%3Ab%2Fc%3Fd%26e%3Df");
+        assert_eq!(percent_encode("caf\u{e9}"), "caf%C3%A9");
+    }
+
+    #[test]
+    fn recovery_code_is_normalized() {
+        let code = generate_recovery_code();
+        assert_eq!(code.len(), RECOVERY_CODE_LENGTH + 1);
+        assert_eq!(normalize_recovery_code(&code.to_lowercase().replace('-', " ")), code);
+        assert_eq!(normalize_recovery_code("abc"), "ABC");
+    }
+}